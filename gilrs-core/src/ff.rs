@@ -0,0 +1,253 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Force feedback effects played by device.
+//!
+//! Unlike `FfDevice::set_ff_state()`, which only changes magnitude of rumble motors, effects
+//! described here are uploaded to device (or its driver) and played by it. This is needed for
+//! condition effects (spring, damper, friction) used by racing wheels and flight sticks, which
+//! depend on position of device's axes.
+//!
+//! Currently only supported on Linux, where effects are uploaded with `EVIOCSFF`. On other
+//! platforms all functions return an error.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use gilrs_core::ff::{Effect, EffectKind, Envelope, Waveform};
+//! use std::time::Duration;
+//! # let gilrs = gilrs_core::Gilrs::new().unwrap();
+//!
+//! let mut device = gilrs.gamepad(0).and_then(|gp| gp.ff_device()).unwrap();
+//! let effect = Effect::new(EffectKind::Periodic {
+//!     waveform: Waveform::Sine,
+//!     period: Duration::from_millis(100),
+//!     magnitude: 0x4000,
+//!     offset: 0,
+//!     phase: 0,
+//!     envelope: Envelope::default(),
+//! })
+//! .length(Duration::from_secs(2));
+//!
+//! let id = device.upload_effect(&effect).unwrap();
+//! device.play_effect(id, 1).unwrap();
+//! ```
+
+use crate::EvCode;
+
+use std::time::Duration;
+
+/// Vibration motor of the device.
+///
+/// Use `Gamepad::ff_motors()` to check which motors are supported by device. Magnitudes of all
+/// motors can be changed with `FfDevice::set_motors()`.
+///
+/// Currently no platform drives trigger motors: `Gamepad::ff_motors()` reports only `Strong` and
+/// `Weak`, and `FfDevice::set_motors()` ignores magnitudes of `LeftTrigger` and `RightTrigger`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Motor {
+    /// Low frequency motor, usually in left grip.
+    Strong,
+    /// High frequency motor, usually in right grip.
+    Weak,
+    /// Motor in left trigger.
+    LeftTrigger,
+    /// Motor in right trigger.
+    RightTrigger,
+}
+
+/// Identifies effect uploaded to `FfDevice`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EffectId(pub(crate) i16);
+
+/// Shape of periodic effect.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Triangle,
+    Sine,
+    SawUp,
+    SawDown,
+}
+
+/// Changes strength of effect at its beginning and end.
+///
+/// Levels are in range from 0 to `u16::MAX`. Default envelope doesn't change strength.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Envelope {
+    /// Duration of attack.
+    pub attack_length: Duration,
+    /// Strength at the beginning of the effect.
+    pub attack_level: u16,
+    /// Duration of fade.
+    pub fade_length: Duration,
+    /// Strength at the end of the effect.
+    pub fade_level: u16,
+}
+
+/// Parameters of condition effect for one axis.
+///
+/// Force is computed from axis position relative to `center`. Positions are in range from
+/// `i16::MIN` to `i16::MAX`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Condition {
+    /// Maximum force when axis is moved to the right (positive direction).
+    pub right_saturation: u16,
+    /// Maximum force when axis is moved to the left (negative direction).
+    pub left_saturation: u16,
+    /// How fast force increases when axis is moved to the right.
+    pub right_coeff: i16,
+    /// How fast force increases when axis is moved to the left.
+    pub left_coeff: i16,
+    /// Size of area around `center` where effect is not active.
+    pub deadband: u16,
+    /// Position of the center.
+    pub center: i16,
+}
+
+/// Type of effect and its parameters.
+///
+/// Condition effects take parameters for X and Y axes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EffectKind {
+    /// Two motors with different frequencies.
+    Rumble { strong: u16, weak: u16 },
+    /// Constant force.
+    Constant { level: i16, envelope: Envelope },
+    /// Force that changes linearly from `start_level` to `end_level`.
+    Ramp {
+        start_level: i16,
+        end_level: i16,
+        envelope: Envelope,
+    },
+    /// Force that changes periodically. `phase` is in range from 0 to `u16::MAX`, which
+    /// corresponds to the whole period.
+    Periodic {
+        waveform: Waveform,
+        period: Duration,
+        magnitude: i16,
+        offset: i16,
+        phase: u16,
+        envelope: Envelope,
+    },
+    /// Force that pulls axes to the center.
+    Spring([Condition; 2]),
+    /// Force proportional to velocity of axes.
+    Damper([Condition; 2]),
+    /// Force that resists movement of axes.
+    Friction([Condition; 2]),
+}
+
+impl EffectKind {
+    /// Returns type of the effect.
+    pub fn effect_type(&self) -> EffectType {
+        match self {
+            EffectKind::Rumble { .. } => EffectType::Rumble,
+            EffectKind::Constant { .. } => EffectType::Constant,
+            EffectKind::Ramp { .. } => EffectType::Ramp,
+            EffectKind::Periodic { .. } => EffectType::Periodic,
+            EffectKind::Spring(_) => EffectType::Spring,
+            EffectKind::Damper(_) => EffectType::Damper,
+            EffectKind::Friction(_) => EffectType::Friction,
+        }
+    }
+}
+
+/// Type of [`EffectKind`](enum.EffectKind.html) without its parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EffectType {
+    Rumble,
+    Constant,
+    Ramp,
+    Periodic,
+    Spring,
+    Damper,
+    Friction,
+}
+
+/// Force feedback features supported by device. Returned by `Gamepad::ff_capabilities()`.
+///
+/// It describes effects that can be uploaded to device. Motors that can be used with
+/// `FfDevice::set_ff_state()` and `FfDevice::set_motors()` are returned by
+/// `Gamepad::ff_motors()`. Currently capabilities are only reported on Linux, on other platforms
+/// they are empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Types of effects that can be uploaded with `FfDevice::upload_effect()`.
+    pub effects: Vec<EffectType>,
+    /// Waveforms supported by periodic effects.
+    pub waveforms: Vec<Waveform>,
+    /// Maximum number of effects that can be uploaded at the same time, if known.
+    pub max_effects: Option<u32>,
+    /// True if device supports changing gain.
+    pub gain: bool,
+    /// True if device supports changing strength of autocenter.
+    pub autocenter: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Trigger {
+    pub(crate) button: EvCode,
+    pub(crate) interval: Duration,
+}
+
+/// Description of effect that can be uploaded to `FfDevice`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Effect {
+    pub(crate) kind: EffectKind,
+    pub(crate) direction: u16,
+    pub(crate) trigger: Option<Trigger>,
+    pub(crate) length: Option<Duration>,
+    pub(crate) delay: Duration,
+}
+
+impl Effect {
+    /// Creates effect with given kind that points down, is not triggered by button and plays
+    /// without delay until stopped.
+    pub fn new(kind: EffectKind) -> Self {
+        Effect {
+            kind,
+            direction: 0,
+            trigger: None,
+            length: None,
+            delay: Duration::from_secs(0),
+        }
+    }
+
+    /// Sets direction of the force. 0 means down, 0x4000 left, 0x8000 up and 0xC000 right.
+    pub fn direction(mut self, direction: u16) -> Self {
+        self.direction = direction;
+
+        self
+    }
+
+    /// Sets button that starts the effect when pressed. Effect is not started again until
+    /// `interval` elapses.
+    pub fn trigger(mut self, button: EvCode, interval: Duration) -> Self {
+        self.trigger = Some(Trigger { button, interval });
+
+        self
+    }
+
+    /// Sets duration of the effect. By default effect is played until stopped.
+    pub fn length(mut self, length: Duration) -> Self {
+        self.length = Some(length);
+
+        self
+    }
+
+    /// Sets delay between playing the effect and its start.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+
+        self
+    }
+}
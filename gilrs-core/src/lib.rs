@@ -6,11 +6,19 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 
 use std::error;
+use std::io;
 use std::time::Duration;
 use std::time::SystemTime;
 
+pub mod ff;
 mod platform;
 mod utils;
+pub mod virtual_gamepad;
+
+#[cfg(target_os = "linux")]
+pub use crate::platform::uinput;
+#[cfg(target_os = "linux")]
+pub use crate::platform::FdChange;
 
 /// True, if Y axis of sticks commonly points downwards.
 pub const IS_Y_AXIS_REVERSED: bool = platform::IS_Y_AXIS_REVERSED;
@@ -26,6 +34,54 @@ impl FfDevice {
     pub fn set_ff_state(&mut self, strong: u16, weak: u16, min_duration: Duration) {
         self.inner.set_ff_state(strong, weak, min_duration)
     }
+
+    /// Sets magnitude of motors. Motors that are not listed are stopped and motors not supported
+    /// by device (see `Gamepad::ff_motors()`) are ignored. If motor is listed more than once,
+    /// magnitudes are added.
+    pub fn set_motors(&mut self, magnitudes: &[(ff::Motor, u16)], min_duration: Duration) {
+        self.inner.set_motors(magnitudes, min_duration)
+    }
+
+    /// Uploads effect to device. Effect is not played until `play_effect()` is called or its
+    /// trigger button is pressed. Uploaded effects are removed when `FfDevice` is dropped.
+    ///
+    /// See [`ff`](ff/index.html) module for details.
+    pub fn upload_effect(&mut self, effect: &ff::Effect) -> io::Result<ff::EffectId> {
+        self.inner.upload_effect(effect)
+    }
+
+    /// Changes parameters of uploaded effect. If effect is playing, it's updated without
+    /// restarting.
+    pub fn update_effect(&mut self, id: ff::EffectId, effect: &ff::Effect) -> io::Result<()> {
+        self.inner.update_effect(id, effect)
+    }
+
+    /// Plays uploaded effect `count` times.
+    pub fn play_effect(&mut self, id: ff::EffectId, count: u16) -> io::Result<()> {
+        self.inner.play_effect(id, count)
+    }
+
+    /// Stops uploaded effect.
+    pub fn stop_effect(&mut self, id: ff::EffectId) -> io::Result<()> {
+        self.inner.stop_effect(id)
+    }
+
+    /// Removes effect from device.
+    pub fn erase_effect(&mut self, id: ff::EffectId) -> io::Result<()> {
+        self.inner.erase_effect(id)
+    }
+
+    /// Sets gain of the device, which scales strength of all effects. `gain` is in range from 0
+    /// to `u16::MAX` (full strength). Check `Capabilities::gain` to see if it's supported.
+    pub fn set_gain(&mut self, gain: u16) -> io::Result<()> {
+        self.inner.set_gain(gain)
+    }
+
+    /// Sets strength of device's autocenter spring, used mostly by racing wheels. 0 disables
+    /// autocenter. Check `Capabilities::autocenter` to see if it's supported.
+    pub fn set_autocenter(&mut self, strength: u16) -> io::Result<()> {
+        self.inner.set_autocenter(strength)
+    }
 }
 
 /// Holds information about gamepad event.
@@ -68,6 +124,7 @@ pub enum EventType {
 
 /// Holds information about expected axis range and deadzone.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct AxisInfo {
     pub min: i32,
     pub max: i32,
@@ -91,6 +148,7 @@ pub struct AxisInfo {
 /// };
 /// ```
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub enum PowerInfo {
     /// Failed to determine power status.
     Unknown,
@@ -107,33 +165,113 @@ pub enum PowerInfo {
 /// Struct used to manage gamepads and retrieve events.
 #[derive(Debug)]
 pub struct Gilrs {
-    inner: platform::Gilrs,
+    inner: Backend,
+}
+
+#[derive(Debug)]
+enum Backend {
+    // Boxed, so `Gilrs` stays small when returned in `Error::NotImplemented`.
+    Platform(Box<platform::Gilrs>),
+    Virtual(virtual_gamepad::Gilrs),
 }
 
 impl Gilrs {
     pub fn new() -> Result<Self, Error> {
         let inner = platform::Gilrs::new().map_err(|e| match e {
-            PlatformError::NotImplemented(inner) => Error::NotImplemented(Gilrs { inner }),
+            PlatformError::NotImplemented(inner) => Error::NotImplemented(Gilrs {
+                inner: Backend::Platform(Box::new(inner)),
+            }),
             PlatformError::Other(e) => Error::Other(e),
         })?;
 
-        Ok(Gilrs { inner })
+        Ok(Gilrs {
+            inner: Backend::Platform(Box::new(inner)),
+        })
+    }
+
+    /// Creates `Gilrs` that doesn't use any real device. Gamepads are created and their events
+    /// injected with returned `Injector`. See [`virtual_gamepad`](virtual_gamepad/index.html)
+    /// module for details.
+    pub fn new_virtual() -> (Self, virtual_gamepad::Injector) {
+        let (inner, injector) = virtual_gamepad::Gilrs::new();
+
+        (
+            Gilrs {
+                inner: Backend::Virtual(inner),
+            },
+            injector,
+        )
     }
 
     /// Returns oldest event or `None` if all events were processed.
     pub fn next_event(&mut self) -> Option<Event> {
-        self.inner.next_event()
+        match self.inner {
+            Backend::Platform(ref mut inner) => inner.next_event(),
+            Backend::Virtual(ref mut inner) => inner.next_event(),
+        }
     }
 
-    /// Borrows `Gamrpad` or return `None` if index is invalid. Returned gamepad may be disconnected.
-    pub fn gamepad(&self, id: usize) -> Option<&Gamepad> {
-        unsafe {
-            let gp: Option<&platform::Gamepad> = self.inner.gamepad(id);
+    /// Returns oldest event, waiting for new one if all events were processed. `None` is returned
+    /// if no event arrived before `timeout` elapsed. If `timeout` is `None`, waits until there is
+    /// an event.
+    ///
+    /// On Linux this function waits for gamepads and udev monitor file descriptors with `epoll`.
+    /// On platforms without gamepad support it returns `None` after `timeout` (or immediately, if
+    /// `timeout` is `None`). On wasm it never blocks and behaves like `next_event()`.
+    pub fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        match self.inner {
+            Backend::Platform(ref mut inner) => inner.next_event_blocking(timeout),
+            Backend::Virtual(ref mut inner) => inner.next_event_blocking(timeout),
+        }
+    }
 
-            gp.map(|gp| &*(gp as *const _ as *const Gamepad))
+    /// Returns file descriptors that `Gilrs` reads events from: udev monitor and all connected
+    /// gamepads. They can be registered in external event loop, `next_event()` should then be
+    /// called until it returns `None` when any of them becomes readable.
+    ///
+    /// The set changes when gamepads are connected or disconnected. Use `take_fd_changes()` after
+    /// handling events to keep registered file descriptors up to date. Virtual backend doesn't use
+    /// any file descriptors.
+    #[cfg(target_os = "linux")]
+    pub fn raw_fds(&self) -> Vec<std::os::unix::io::RawFd> {
+        match self.inner {
+            Backend::Platform(ref inner) => inner.raw_fds(),
+            Backend::Virtual(_) => Vec::new(),
         }
     }
 
+    /// Returns changes in set of file descriptors returned by `raw_fds()` since last call to this
+    /// function. Changes are only recorded when hotplug events are processed by `next_event()`.
+    /// Gamepads that were both connected and disconnected since last call are not reported.
+    #[cfg(target_os = "linux")]
+    pub fn take_fd_changes(&mut self) -> Vec<FdChange> {
+        match self.inner {
+            Backend::Platform(ref mut inner) => inner.take_fd_changes(),
+            Backend::Virtual(_) => Vec::new(),
+        }
+    }
+
+    /// Returns `Waiter` that can be used to wait for new events from other thread, without
+    /// borrowing `Gilrs`.
+    pub fn waiter(&self) -> Waiter {
+        let inner = match self.inner {
+            Backend::Platform(ref inner) => WaiterBackend::Platform(inner.waiter()),
+            Backend::Virtual(ref inner) => WaiterBackend::Virtual(inner.waiter()),
+        };
+
+        Waiter { inner }
+    }
+
+    /// Borrows `Gamrpad` or return `None` if index is invalid. Returned gamepad may be disconnected.
+    pub fn gamepad(&self, id: usize) -> Option<Gamepad<'_>> {
+        let inner = match self.inner {
+            Backend::Platform(ref inner) => GamepadBackend::Platform(inner.gamepad(id)?),
+            Backend::Virtual(ref inner) => GamepadBackend::Virtual(inner.gamepad(id)?),
+        };
+
+        Some(Gamepad { inner })
+    }
+
     /// Returns id greater than id of last connected gamepad. The returned value is only hint
     /// and may be much larger than number of observed gamepads. For example, it may return maximum
     /// number of connected gamepads on platforms when this limit is small.
@@ -141,26 +279,73 @@ impl Gilrs {
     /// `gamepad(id)` should return `Some` if using id that is smaller than value returned from this
     /// function.
     pub fn last_gamepad_hint(&self) -> usize {
-        self.inner.last_gamepad_hint()
+        match self.inner {
+            Backend::Platform(ref inner) => inner.last_gamepad_hint(),
+            Backend::Virtual(ref inner) => inner.last_gamepad_hint(),
+        }
     }
 }
 
-/// Provides information about gamepad.
+/// Waits for new events without borrowing `Gilrs`.
+///
+/// Created with [`Gilrs::waiter()`](struct.Gilrs.html#method.waiter). It can be moved to other
+/// thread, for example to wake up async task when events become available. Events are still
+/// returned only by `Gilrs::next_event()`.
 #[derive(Debug)]
-#[repr(transparent)]
-pub struct Gamepad {
-    inner: platform::Gamepad,
+pub struct Waiter {
+    inner: WaiterBackend,
+}
+
+#[derive(Debug)]
+enum WaiterBackend {
+    Platform(platform::Waiter),
+    Virtual(virtual_gamepad::Waiter),
+}
+
+impl Waiter {
+    /// Blocks until there may be new events or `timeout` elapses. Returns `false` if `timeout`
+    /// elapsed.
+    ///
+    /// Spurious wakeups are possible, so `true` doesn't guarantee that `Gilrs::next_event()` will
+    /// return `Some`. On Linux this function waits for the same file descriptors as
+    /// `Gilrs::next_event_blocking()`, but gamepad that ended the wait doesn't end it again until
+    /// its events are read with `Gilrs::next_event()`. On Windows and macOS it sleeps for a short
+    /// time and always returns `true`. On wasm it returns `true` immediately.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        match self.inner {
+            WaiterBackend::Platform(ref inner) => inner.wait(timeout),
+            WaiterBackend::Virtual(ref inner) => inner.wait(timeout),
+        }
+    }
+}
+
+/// Provides information about gamepad.
+#[derive(Copy, Clone, Debug)]
+pub struct Gamepad<'a> {
+    inner: GamepadBackend<'a>,
 }
 
-impl Gamepad {
+#[derive(Copy, Clone, Debug)]
+enum GamepadBackend<'a> {
+    Platform(&'a platform::Gamepad),
+    Virtual(&'a virtual_gamepad::Gamepad),
+}
+
+impl<'a> Gamepad<'a> {
     /// Returns name of gamepad.
-    pub fn name(&self) -> &str {
-        self.inner.name()
+    pub fn name(&self) -> &'a str {
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.name(),
+            GamepadBackend::Virtual(inner) => inner.name(),
+        }
     }
 
     /// Returns true if gamepad is connected.
     pub fn is_connected(&self) -> bool {
-        self.inner.is_connected()
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.is_connected(),
+            GamepadBackend::Virtual(inner) => inner.is_connected(),
+        }
     }
 
     /// Returns UUID that represents gamepad model.
@@ -171,46 +356,82 @@ impl Gamepad {
     /// It is recommended to process with the [UUID crate](https://crates.io/crates/uuid).
     /// Use `Uuid::from_bytes` method to create a `Uuid` from the returned bytes.
     pub fn uuid(&self) -> [u8; 16] {
-        *self.inner.uuid().as_bytes()
+        let uuid = match self.inner {
+            GamepadBackend::Platform(inner) => inner.uuid(),
+            GamepadBackend::Virtual(inner) => inner.uuid(),
+        };
+
+        *uuid.as_bytes()
     }
 
     /// Returns device's power supply state.
     pub fn power_info(&self) -> PowerInfo {
-        self.inner.power_info()
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.power_info(),
+            GamepadBackend::Virtual(inner) => inner.power_info(),
+        }
     }
 
     /// Returns true if force feedback is supported by device,
     pub fn is_ff_supported(&self) -> bool {
-        self.inner.is_ff_supported()
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.is_ff_supported(),
+            GamepadBackend::Virtual(_) => false,
+        }
+    }
+
+    /// Returns vibration motors supported by device. Slice is empty if force feedback is not
+    /// supported. Trigger motors are never reported (see [`Motor`](ff/enum.Motor.html)).
+    pub fn ff_motors(&self) -> &'a [ff::Motor] {
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.ff_motors(),
+            GamepadBackend::Virtual(_) => &[],
+        }
+    }
+
+    /// Returns force feedback effects and features supported by device.
+    pub fn ff_capabilities(&self) -> ff::Capabilities {
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.ff_capabilities(),
+            GamepadBackend::Virtual(_) => Default::default(),
+        }
     }
 
     /// Creates `FfDevice` corresponding to this gamepad.
     pub fn ff_device(&self) -> Option<FfDevice> {
-        self.inner.ff_device().map(|inner| FfDevice { inner })
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.ff_device().map(|inner| FfDevice { inner }),
+            GamepadBackend::Virtual(_) => None,
+        }
     }
 
     /// Returns slice with EvCodes that may appear in button related events.
-    pub fn buttons(&self) -> &[EvCode] {
-        unsafe {
-            let bt: &[platform::EvCode] = self.inner.buttons();
+    pub fn buttons(&self) -> &'a [EvCode] {
+        let bt: &[platform::EvCode] = match self.inner {
+            GamepadBackend::Platform(inner) => inner.buttons(),
+            GamepadBackend::Virtual(inner) => inner.buttons(),
+        };
 
-            &*(bt as *const _ as *const [EvCode])
-        }
+        unsafe { &*(bt as *const _ as *const [EvCode]) }
     }
 
     /// Returns slice with EvCodes that may appear in axis related events.
-    pub fn axes(&self) -> &[EvCode] {
-        unsafe {
-            let ax: &[platform::EvCode] = self.inner.axes();
+    pub fn axes(&self) -> &'a [EvCode] {
+        let ax: &[platform::EvCode] = match self.inner {
+            GamepadBackend::Platform(inner) => inner.axes(),
+            GamepadBackend::Virtual(inner) => inner.axes(),
+        };
 
-            &*(ax as *const _ as *const [EvCode])
-        }
+        unsafe { &*(ax as *const _ as *const [EvCode]) }
     }
 
     /// Returns information about specific axis. `None` may be returned if device doesn't have axis
     /// with provided `EvCode`.
-    pub fn axis_info(&self, nec: EvCode) -> Option<&AxisInfo> {
-        self.inner.axis_info(nec.0)
+    pub fn axis_info(&self, nec: EvCode) -> Option<&'a AxisInfo> {
+        match self.inner {
+            GamepadBackend::Platform(inner) => inner.axis_info(nec.0),
+            GamepadBackend::Virtual(inner) => inner.axis_info(nec.0),
+        }
     }
 }
 
@@ -219,7 +440,7 @@ use serde::{Deserialize, Serialize};
 
 /// Platform specific representation of axis or button.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[repr(transparent)]
 pub struct EvCode(platform::EvCode);
 
@@ -7,10 +7,13 @@
 #![allow(unused_variables)]
 
 use super::FfDevice;
+use crate::ff::{Capabilities, Motor};
 use crate::{AxisInfo, Event, PlatformError, PowerInfo};
 use uuid::Uuid;
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::thread;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct Gilrs {}
@@ -24,6 +27,18 @@ impl Gilrs {
         None
     }
 
+    pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        if let Some(timeout) = timeout {
+            thread::sleep(timeout);
+        }
+
+        None
+    }
+
+    pub fn waiter(&self) -> Waiter {
+        Waiter
+    }
+
     pub fn gamepad(&self, id: usize) -> Option<&Gamepad> {
         None
     }
@@ -34,6 +49,23 @@ impl Gilrs {
     }
 }
 
+/// Waits for new events from backend.
+#[derive(Debug)]
+pub struct Waiter;
+
+impl Waiter {
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        match timeout {
+            Some(timeout) => thread::sleep(timeout),
+            None => loop {
+                thread::park();
+            },
+        }
+
+        false
+    }
+}
+
 #[derive(Debug)]
 pub struct Gamepad {
     _priv: u8, // required for `#[repr(transparent)]`
@@ -56,6 +88,14 @@ impl Gamepad {
         false
     }
 
+    pub fn ff_motors(&self) -> &[Motor] {
+        &[]
+    }
+
+    pub fn ff_capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Creates Ffdevice corresponding to this gamepad.
     pub fn ff_device(&self) -> Option<FfDevice> {
         Some(FfDevice)
@@ -81,7 +121,7 @@ impl Gamepad {
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct EvCode(u16);
 
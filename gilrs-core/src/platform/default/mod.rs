@@ -8,7 +8,7 @@ mod ff;
 mod gamepad;
 
 pub use self::ff::Device as FfDevice;
-pub use self::gamepad::{native_ev_codes, EvCode, Gamepad, Gilrs};
+pub use self::gamepad::{native_ev_codes, EvCode, Gamepad, Gilrs, Waiter};
 
 // True, if Y axis of sticks points downwards.
 pub const IS_Y_AXIS_REVERSED: bool = false;
@@ -8,10 +8,13 @@
 use std::fs::File;
 use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
 use std::os::unix::io::AsRawFd;
-use std::u16::MAX as U16_MAX;
-use std::{mem, slice};
+use std::{mem, ptr, slice};
 
-use super::ioctl::{self, ff_effect, ff_replay, ff_rumble_effect, input_event};
+use super::ioctl::{
+    self, ff_condition_effect, ff_constant_effect, ff_effect, ff_envelope, ff_periodic_effect,
+    ff_ramp_effect, ff_replay, ff_rumble_effect, ff_trigger, input_event, nix_to_io,
+};
+use crate::ff::{Condition, Effect, EffectId, EffectKind, Envelope, Motor, Waveform};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -46,12 +49,7 @@ impl Device {
     }
 
     pub fn set_ff_state(&mut self, strong: u16, weak: u16, min_duration: Duration) {
-        let duration = min_duration.as_secs() * 1000 + u64::from(min_duration.subsec_millis());
-        let duration = if duration > u64::from(U16_MAX) {
-            U16_MAX
-        } else {
-            duration as u16
-        };
+        let duration = duration_to_ms(min_duration);
 
         let mut effect = ff_effect {
             type_: FF_RUMBLE,
@@ -80,14 +78,86 @@ impl Device {
             }
         };
 
+        if let Err(e) = self.write_ff_event(self.effect as u16, 1) {
+            error!("Failed to set ff state: {}", e);
+        }
+    }
+
+    pub fn set_motors(&mut self, magnitudes: &[(Motor, u16)], min_duration: Duration) {
+        let magnitude = |motor| {
+            magnitudes
+                .iter()
+                .filter(|&&(m, _)| m == motor)
+                .fold(0u16, |acc, &(_, mg)| acc.saturating_add(mg))
+        };
+
+        self.set_ff_state(
+            magnitude(Motor::Strong),
+            magnitude(Motor::Weak),
+            min_duration,
+        );
+    }
+
+    pub fn upload_effect(&mut self, effect: &Effect) -> IoResult<EffectId> {
+        let mut raw = to_ff_effect(effect);
+        raw.id = -1;
+        self.upload(&mut raw)?;
+
+        Ok(EffectId(raw.id))
+    }
+
+    pub fn update_effect(&mut self, id: EffectId, effect: &Effect) -> IoResult<()> {
+        let mut raw = to_ff_effect(effect);
+        raw.id = id.0;
+
+        self.upload(&mut raw)
+    }
+
+    pub fn play_effect(&mut self, id: EffectId, count: u16) -> IoResult<()> {
+        self.write_ff_event(id.0 as u16, i32::from(count))
+    }
+
+    pub fn stop_effect(&mut self, id: EffectId) -> IoResult<()> {
+        self.write_ff_event(id.0 as u16, 0)
+    }
+
+    pub fn erase_effect(&mut self, id: EffectId) -> IoResult<()> {
+        #[cfg(target_pointer_width = "64")]
+        let effect = id.0 as u64;
+        #[cfg(target_pointer_width = "32")]
+        let effect = id.0 as u32;
+
+        unsafe { ioctl::eviocrmff(self.file.as_raw_fd(), effect) }
+            .map(|_| ())
+            .map_err(nix_to_io)
+    }
+
+    pub fn set_gain(&mut self, gain: u16) -> IoResult<()> {
+        self.write_ff_event(FF_GAIN, i32::from(gain))
+    }
+
+    pub fn set_autocenter(&mut self, strength: u16) -> IoResult<()> {
+        self.write_ff_event(FF_AUTOCENTER, i32::from(strength))
+    }
+
+    fn upload(&mut self, effect: &mut ff_effect) -> IoResult<()> {
+        // Kernel writes ID of new effect back to `effect`.
+        #[allow(clippy::unnecessary_mut_passed)]
+        unsafe { ioctl::eviocsff(self.file.as_raw_fd(), effect) }
+            .map(|_| ())
+            .map_err(nix_to_io)
+    }
+
+    /// Writes `EV_FF` event. `code` is ID of effect or `FF_GAIN`/`FF_AUTOCENTER`.
+    fn write_ff_event(&mut self, code: u16, value: i32) -> IoResult<()> {
         let time = libc::timeval {
             tv_sec: 0,
             tv_usec: 0,
         };
         let ev = input_event {
             type_: EV_FF,
-            code: self.effect as u16,
-            value: 1,
+            code,
+            value,
             time,
         };
 
@@ -95,9 +165,9 @@ impl Device {
         let s = unsafe { slice::from_raw_parts(&ev as *const _ as *const u8, size) };
 
         match self.file.write(s) {
-            Ok(s) if s == size => (),
+            Ok(s) if s == size => Ok(()),
             Ok(_) => unreachable!(),
-            Err(e) => error!("Failed to set ff state: {}", e),
+            Err(e) => Err(e),
         }
     }
 }
@@ -118,5 +188,202 @@ impl Drop for Device {
     }
 }
 
-const EV_FF: u16 = 0x15;
-const FF_RUMBLE: u16 = 0x50;
+fn to_ff_effect(effect: &Effect) -> ff_effect {
+    let mut raw = ff_effect {
+        type_: 0,
+        id: -1,
+        direction: effect.direction,
+        trigger: effect
+            .trigger
+            .map(|trigger| ff_trigger {
+                button: (trigger.button.0).code(),
+                interval: duration_to_ms(trigger.interval),
+            })
+            .unwrap_or_default(),
+        replay: ff_replay {
+            length: effect.length.map_or(0, duration_to_ms),
+            delay: duration_to_ms(effect.delay),
+        },
+        u: Default::default(),
+    };
+
+    // `u` is a union in C, all effects fit in it and it's aligned to 8 (or 4) bytes.
+    unsafe {
+        let u = &mut raw.u as *mut _;
+
+        raw.type_ = match effect.kind {
+            EffectKind::Rumble { strong, weak } => {
+                *(u as *mut ff_rumble_effect) = ff_rumble_effect {
+                    strong_magnitude: strong,
+                    weak_magnitude: weak,
+                };
+                FF_RUMBLE
+            }
+            EffectKind::Constant { level, envelope } => {
+                *(u as *mut ff_constant_effect) = ff_constant_effect {
+                    level,
+                    envelope: to_ff_envelope(&envelope),
+                };
+                FF_CONSTANT
+            }
+            EffectKind::Ramp {
+                start_level,
+                end_level,
+                envelope,
+            } => {
+                *(u as *mut ff_ramp_effect) = ff_ramp_effect {
+                    start_level,
+                    end_level,
+                    envelope: to_ff_envelope(&envelope),
+                };
+                FF_RAMP
+            }
+            EffectKind::Periodic {
+                waveform,
+                period,
+                magnitude,
+                offset,
+                phase,
+                envelope,
+            } => {
+                *(u as *mut ff_periodic_effect) = ff_periodic_effect {
+                    waveform: match waveform {
+                        Waveform::Square => FF_SQUARE,
+                        Waveform::Triangle => FF_TRIANGLE,
+                        Waveform::Sine => FF_SINE,
+                        Waveform::SawUp => FF_SAW_UP,
+                        Waveform::SawDown => FF_SAW_DOWN,
+                    },
+                    period: duration_to_ms(period),
+                    magnitude,
+                    offset,
+                    phase,
+                    envelope: to_ff_envelope(&envelope),
+                    custom_len: 0,
+                    custom_data: ptr::null_mut(),
+                };
+                FF_PERIODIC
+            }
+            EffectKind::Spring(ref conditions) => {
+                *(u as *mut [ff_condition_effect; 2]) = to_ff_conditions(conditions);
+                FF_SPRING
+            }
+            EffectKind::Damper(ref conditions) => {
+                *(u as *mut [ff_condition_effect; 2]) = to_ff_conditions(conditions);
+                FF_DAMPER
+            }
+            EffectKind::Friction(ref conditions) => {
+                *(u as *mut [ff_condition_effect; 2]) = to_ff_conditions(conditions);
+                FF_FRICTION
+            }
+        };
+    }
+
+    raw
+}
+
+fn to_ff_envelope(envelope: &Envelope) -> ff_envelope {
+    ff_envelope {
+        attack_length: duration_to_ms(envelope.attack_length),
+        attack_level: envelope.attack_level,
+        fade_length: duration_to_ms(envelope.fade_length),
+        fade_level: envelope.fade_level,
+    }
+}
+
+fn to_ff_conditions(conditions: &[Condition; 2]) -> [ff_condition_effect; 2] {
+    let convert = |c: &Condition| ff_condition_effect {
+        right_saturation: c.right_saturation,
+        left_saturation: c.left_saturation,
+        right_coeff: c.right_coeff,
+        left_coeff: c.left_coeff,
+        deadband: c.deadband,
+        center: c.center,
+    };
+
+    [convert(&conditions[0]), convert(&conditions[1])]
+}
+
+/// Converts duration to milliseconds, saturating at `u16::MAX`.
+fn duration_to_ms(duration: Duration) -> u16 {
+    duration.as_millis().min(0xffff) as u16
+}
+
+pub(super) const EV_FF: u16 = 0x15;
+pub(super) const FF_RUMBLE: u16 = 0x50;
+pub(super) const FF_PERIODIC: u16 = 0x51;
+pub(super) const FF_CONSTANT: u16 = 0x52;
+pub(super) const FF_SPRING: u16 = 0x53;
+pub(super) const FF_FRICTION: u16 = 0x54;
+pub(super) const FF_DAMPER: u16 = 0x55;
+pub(super) const FF_RAMP: u16 = 0x57;
+pub(super) const FF_SQUARE: u16 = 0x58;
+pub(super) const FF_TRIANGLE: u16 = 0x59;
+pub(super) const FF_SINE: u16 = 0x5a;
+pub(super) const FF_SAW_UP: u16 = 0x5b;
+pub(super) const FF_SAW_DOWN: u16 = 0x5c;
+pub(super) const FF_GAIN: u16 = 0x60;
+pub(super) const FF_AUTOCENTER: u16 = 0x61;
+pub(super) const FF_MAX: u16 = 0x7f;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native_ev_codes::BTN_SOUTH;
+
+    #[test]
+    fn effect_conversion() {
+        let effect = Effect::new(EffectKind::Periodic {
+            waveform: Waveform::Triangle,
+            period: Duration::from_millis(50),
+            magnitude: -100,
+            offset: 10,
+            phase: 0x4000,
+            envelope: Envelope {
+                attack_length: Duration::from_millis(200),
+                attack_level: 0,
+                fade_length: Duration::from_secs(100),
+                fade_level: 0,
+            },
+        })
+        .direction(0xC000)
+        .delay(Duration::from_millis(5));
+
+        let raw = to_ff_effect(&effect);
+        assert_eq!(raw.type_, FF_PERIODIC);
+        assert_eq!(raw.direction, 0xC000);
+        assert_eq!(raw.replay.length, 0);
+        assert_eq!(raw.replay.delay, 5);
+
+        let periodic = unsafe { *(&raw.u as *const _ as *const ff_periodic_effect) };
+        assert_eq!(periodic.waveform, FF_TRIANGLE);
+        assert_eq!(periodic.period, 50);
+        assert_eq!(periodic.magnitude, -100);
+        assert_eq!(periodic.offset, 10);
+        assert_eq!(periodic.phase, 0x4000);
+        assert_eq!(periodic.envelope.attack_length, 200);
+        assert_eq!(periodic.envelope.fade_length, 0xffff);
+
+        let x = Condition {
+            right_coeff: 0x2000,
+            left_coeff: -0x2000,
+            center: 5,
+            ..Default::default()
+        };
+        let effect = Effect::new(EffectKind::Spring([x, Condition::default()]))
+            .length(Duration::from_secs(1))
+            .trigger(BTN_SOUTH, Duration::from_millis(300));
+
+        let raw = to_ff_effect(&effect);
+        assert_eq!(raw.type_, FF_SPRING);
+        assert_eq!(raw.replay.length, 1000);
+        assert_eq!(raw.trigger.button, 0x130);
+        assert_eq!(raw.trigger.interval, 300);
+
+        let conditions = unsafe { *(&raw.u as *const _ as *const [ff_condition_effect; 2]) };
+        assert_eq!(conditions[0].right_coeff, 0x2000);
+        assert_eq!(conditions[0].left_coeff, -0x2000);
+        assert_eq!(conditions[0].center, 5);
+        assert_eq!(conditions[1].right_coeff, 0);
+    }
+}
@@ -6,9 +6,14 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::ff::Device as FfDevice;
+use super::ff::{
+    FF_AUTOCENTER, FF_CONSTANT, FF_DAMPER, FF_FRICTION, FF_GAIN, FF_MAX, FF_PERIODIC, FF_RAMP,
+    FF_RUMBLE, FF_SAW_DOWN, FF_SAW_UP, FF_SINE, FF_SPRING, FF_SQUARE, FF_TRIANGLE,
+};
 use super::ioctl;
 use super::ioctl::{input_absinfo, input_event};
 use super::udev::*;
+use crate::ff::{Capabilities, EffectType, Motor, Waveform};
 use crate::utils;
 use crate::{AxisInfo, Event, EventType};
 use crate::{PlatformError, PowerInfo};
@@ -20,17 +25,20 @@ use vec_map::VecMap;
 use std::error;
 use std::ffi::CStr;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
 use std::mem::{self, MaybeUninit};
 use std::ops::Index;
 use std::os::raw::c_char;
-use std::str;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{str, thread};
 
 #[derive(Debug)]
 pub struct Gilrs {
     gamepads: Vec<Gamepad>,
     monitor: Monitor,
     event_counter: usize,
+    epoll_fd: c::c_int,
+    fd_changes: Vec<FdChange>,
 }
 
 impl Gilrs {
@@ -66,11 +74,28 @@ impl Gilrs {
             None => return Err(PlatformError::Other(Box::new(Error::UdevMonitor))),
         };
 
-        Ok(Gilrs {
+        let epoll_fd = unsafe { c::epoll_create1(c::EPOLL_CLOEXEC) };
+        if epoll_fd < 0 {
+            error!(
+                "Failed to create epoll instance: {}",
+                io::Error::last_os_error()
+            );
+        }
+
+        let gilrs = Gilrs {
             gamepads,
             monitor,
             event_counter: 0,
-        })
+            epoll_fd,
+            fd_changes: Vec::new(),
+        };
+
+        epoll_ctl(epoll_fd, c::EPOLL_CTL_ADD, gilrs.monitor.fd(), c::EPOLLIN);
+        for gamepad in &gilrs.gamepads {
+            epoll_ctl(epoll_fd, c::EPOLL_CTL_ADD, gamepad.fd, GAMEPAD_EPOLL_EVENTS);
+        }
+
+        Ok(gilrs)
     }
 
     pub(crate) fn next_event(&mut self) -> Option<Event> {
@@ -78,6 +103,7 @@ impl Gilrs {
             return Some(event);
         }
 
+        let epoll_fd = self.epoll_fd;
         loop {
             let gamepad = match self.gamepads.get_mut(self.event_counter) {
                 Some(gp) => gp,
@@ -101,6 +127,13 @@ impl Gilrs {
                     });
                 }
                 None => {
+                    // Gamepad's fd is registered with `EPOLLONESHOT` and may have been disabled
+                    // by `Waiter`. It's only enabled again here, after all events were read, so
+                    // waiter thread never has to modify epoll set.
+                    if gamepad.has_read {
+                        gamepad.has_read = false;
+                        epoll_ctl(epoll_fd, c::EPOLL_CTL_MOD, gamepad.fd, GAMEPAD_EPOLL_EVENTS);
+                    }
                     self.event_counter += 1;
                     continue;
                 }
@@ -108,6 +141,57 @@ impl Gilrs {
         }
     }
 
+    pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            if let Some(event) = self.next_event() {
+                return Some(event);
+            }
+
+            let timeout_ms = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return None;
+                    }
+
+                    duration_to_ms_ceil(deadline - now)
+                }
+                None => -1,
+            };
+
+            if let Err(err) = wait_for_events(self.epoll_fd, timeout_ms) {
+                error!("Failed to wait for events: {}", err);
+                return None;
+            }
+        }
+    }
+
+    pub fn raw_fds(&self) -> Vec<c::c_int> {
+        let monitor_fd = self.monitor.fd();
+
+        Some(monitor_fd)
+            .into_iter()
+            .chain(self.gamepads.iter().map(|gp| gp.fd))
+            .filter(|&fd| fd >= 0)
+            .collect()
+    }
+
+    pub fn take_fd_changes(&mut self) -> Vec<FdChange> {
+        mem::take(&mut self.fd_changes)
+    }
+
+    pub fn waiter(&self) -> Waiter {
+        let epoll_fd = if self.epoll_fd >= 0 {
+            unsafe { c::fcntl(self.epoll_fd, c::F_DUPFD_CLOEXEC, 0) }
+        } else {
+            -1
+        };
+
+        Waiter { epoll_fd }
+    }
+
     pub fn gamepad(&self, id: usize) -> Option<&Gamepad> {
         self.gamepads.get(id)
     }
@@ -136,6 +220,14 @@ impl Gilrs {
 
                 if action == cstr_new(b"add\0") {
                     if let Some(gamepad) = Gamepad::open(&dev) {
+                        epoll_ctl(
+                            self.epoll_fd,
+                            c::EPOLL_CTL_ADD,
+                            gamepad.fd,
+                            GAMEPAD_EPOLL_EVENTS,
+                        );
+                        self.fd_changes.push(FdChange::Added(gamepad.fd));
+
                         if let Some(id) = self
                             .gamepads
                             .iter()
@@ -155,6 +247,20 @@ impl Gilrs {
                             .iter()
                             .position(|gp| is_eq_cstr_str(devnode, &gp.devpath) && gp.is_connected)
                         {
+                            let fd = self.gamepads[id].fd;
+                            // Gamepad that was added after last `take_fd_changes()` was never
+                            // seen by caller, so it's enough to forget that it was added. This
+                            // also keeps `fd_changes` from growing if it's never taken.
+                            match self
+                                .fd_changes
+                                .iter()
+                                .rposition(|&change| change == FdChange::Added(fd))
+                            {
+                                Some(idx) => {
+                                    self.fd_changes.remove(idx);
+                                }
+                                None => self.fd_changes.push(FdChange::Removed(fd)),
+                            }
                             self.gamepads[id].disconnect();
                             return Some(Event::new(id, EventType::Disconnected));
                         } else {
@@ -168,6 +274,126 @@ impl Gilrs {
     }
 }
 
+impl Drop for Gilrs {
+    fn drop(&mut self) {
+        if self.epoll_fd >= 0 {
+            unsafe {
+                c::close(self.epoll_fd);
+            }
+        }
+    }
+}
+
+/// Change in set of file descriptors returned by `Gilrs::raw_fds()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FdChange {
+    /// Gamepad was connected and its file descriptor should be watched.
+    Added(c::c_int),
+    /// Gamepad was disconnected. File descriptor is already closed and its number may be reused
+    /// by later `Added`.
+    Removed(c::c_int),
+}
+
+/// Waits for new events from backend.
+#[derive(Debug)]
+pub struct Waiter {
+    epoll_fd: c::c_int,
+}
+
+impl Waiter {
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let timeout_ms = timeout.map_or(-1, duration_to_ms_ceil);
+
+        match wait_for_events(self.epoll_fd, timeout_ms) {
+            Ok(ready) => ready,
+            Err(err) => {
+                error!("Failed to wait for events: {}", err);
+                false
+            }
+        }
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        if self.epoll_fd >= 0 {
+            unsafe {
+                c::close(self.epoll_fd);
+            }
+        }
+    }
+}
+
+/// Waits until one of file descriptors registered in `epoll_fd` is ready or `timeout_ms`
+/// elapses. Returns true if there may be new events.
+fn wait_for_events(epoll_fd: c::c_int, timeout_ms: c::c_int) -> io::Result<bool> {
+    if epoll_fd < 0 {
+        // Fall back to polling.
+        let sleep = if timeout_ms < 0 {
+            10
+        } else {
+            timeout_ms.min(10)
+        };
+        thread::sleep(Duration::from_millis(sleep as u64));
+
+        return Ok(true);
+    }
+
+    let mut events: [c::epoll_event; 16] = unsafe { mem::zeroed() };
+    let n = unsafe {
+        c::epoll_wait(
+            epoll_fd,
+            events.as_mut_ptr(),
+            events.len() as c::c_int,
+            timeout_ms,
+        )
+    };
+
+    if n < 0 {
+        let err = io::Error::last_os_error();
+        return if err.kind() == io::ErrorKind::Interrupted {
+            Ok(false)
+        } else {
+            Err(err)
+        };
+    }
+
+    Ok(n > 0)
+}
+
+/// Events of gamepads' file descriptors. Gamepad is disabled in epoll set after it's reported once,
+/// so removed device, which is ready until udev event is processed and its fd closed, doesn't
+/// wake waiting threads all the time. `Gilrs::next_event()` enables it again after reading events.
+const GAMEPAD_EPOLL_EVENTS: c::c_int = c::EPOLLIN | c::EPOLLONESHOT;
+
+fn epoll_ctl(epoll_fd: c::c_int, op: c::c_int, fd: c::c_int, events: c::c_int) {
+    if epoll_fd < 0 || fd < 0 {
+        return;
+    }
+
+    let mut event = c::epoll_event {
+        events: events as u32,
+        u64: fd as u64,
+    };
+
+    if unsafe { c::epoll_ctl(epoll_fd, op, fd, &mut event) } < 0 {
+        error!(
+            "Failed to add or modify fd {} in epoll: {}",
+            fd,
+            io::Error::last_os_error()
+        );
+    }
+}
+
+fn duration_to_ms_ceil(d: Duration) -> c::c_int {
+    let mut ms = d.as_millis();
+    if Duration::from_millis(ms as u64) < d {
+        ms += 1;
+    }
+
+    ms.min(0x7fff_ffff) as c::c_int
+}
+
 fn is_eq_cstr_str(l: &CStr, r: &str) -> bool {
     unsafe {
         let mut l_ptr = l.as_ptr();
@@ -234,6 +460,7 @@ pub struct Gamepad {
     fd: i32,
     axes_info: AxesInfo,
     ff_supported: bool,
+    ff_capabilities: Capabilities,
     devpath: String,
     name: String,
     uuid: Uuid,
@@ -247,6 +474,8 @@ pub struct Gamepad {
     axes: Vec<EvCode>,
     buttons: Vec<EvCode>,
     is_connected: bool,
+    /// Events were read since fd was last enabled in epoll set.
+    has_read: bool,
 }
 
 impl Gamepad {
@@ -284,13 +513,15 @@ impl Gamepad {
         });
 
         let axesi = AxesInfo::new(fd);
-        let ff_supported = Self::test_ff(fd);
+        let ff_capabilities = Self::read_ff_capabilities(fd);
+        let ff_supported = Self::test_ff(&ff_capabilities);
         let (cap, status) = Self::battery_fd(&dev);
 
         let mut gamepad = Gamepad {
             fd,
             axes_info: axesi,
             ff_supported,
+            ff_capabilities,
             devpath: path.to_string_lossy().into_owned(),
             name,
             uuid,
@@ -302,6 +533,7 @@ impl Gamepad {
             axes: Vec::new(),
             buttons: Vec::new(),
             is_connected: true,
+            has_read: false,
         };
 
         gamepad.collect_axes_and_buttons();
@@ -366,26 +598,68 @@ impl Gamepad {
         }
     }
 
-    fn test_ff(fd: i32) -> bool {
-        unsafe {
-            let mut ff_bits = [0u8; (FF_MAX / 8) as usize + 1];
-            if ioctl::eviocgbit(
+    fn read_ff_capabilities(fd: i32) -> Capabilities {
+        let mut ff_bits = [0u8; (FF_MAX / 8) as usize + 1];
+        let res = unsafe {
+            ioctl::eviocgbit(
                 fd,
                 u32::from(EV_FF),
                 ff_bits.len() as i32,
                 ff_bits.as_mut_ptr(),
-            ) >= 0
-            {
-                utils::test_bit(FF_SQUARE, &ff_bits)
-                    && utils::test_bit(FF_TRIANGLE, &ff_bits)
-                    && utils::test_bit(FF_SINE, &ff_bits)
-                    && utils::test_bit(FF_GAIN, &ff_bits)
-            } else {
-                false
-            }
+            )
+        };
+        if res < 0 {
+            return Capabilities::default();
+        }
+
+        let has = |bit| utils::test_bit(bit, &ff_bits);
+        let effects = [
+            (FF_RUMBLE, EffectType::Rumble),
+            (FF_CONSTANT, EffectType::Constant),
+            (FF_RAMP, EffectType::Ramp),
+            (FF_PERIODIC, EffectType::Periodic),
+            (FF_SPRING, EffectType::Spring),
+            (FF_DAMPER, EffectType::Damper),
+            (FF_FRICTION, EffectType::Friction),
+        ];
+        let waveforms = [
+            (FF_SQUARE, Waveform::Square),
+            (FF_TRIANGLE, Waveform::Triangle),
+            (FF_SINE, Waveform::Sine),
+            (FF_SAW_UP, Waveform::SawUp),
+            (FF_SAW_DOWN, Waveform::SawDown),
+        ];
+
+        let mut max_effects = 0;
+        let max_effects = match unsafe { ioctl::eviocgeffects(fd, &mut max_effects) } {
+            Ok(_) => Some(max_effects as u32),
+            Err(_) => None,
+        };
+
+        Capabilities {
+            effects: effects
+                .iter()
+                .filter(|&&(bit, _)| has(bit))
+                .map(|&(_, ty)| ty)
+                .collect(),
+            waveforms: waveforms
+                .iter()
+                .filter(|&&(bit, _)| has(bit))
+                .map(|&(_, waveform)| waveform)
+                .collect(),
+            max_effects,
+            gain: has(FF_GAIN),
+            autocenter: has(FF_AUTOCENTER),
         }
     }
 
+    fn test_ff(capabilities: &Capabilities) -> bool {
+        [Waveform::Square, Waveform::Triangle, Waveform::Sine]
+            .iter()
+            .all(|waveform| capabilities.waveforms.contains(waveform))
+            && capabilities.gain
+    }
+
     fn is_gamepad(&self) -> bool {
         // TODO: improve it (for example check for buttons in range)
         !self.buttons.is_empty() && self.axes.len() >= 2
@@ -541,6 +815,7 @@ impl Gamepad {
                 } else {
                     let n = n as usize / size;
                     trace!("Got {} new events", n);
+                    self.has_read = true;
                     for ev in event_buf[1..n].iter().rev() {
                         self.events.push(ev.assume_init());
                     }
@@ -670,6 +945,18 @@ impl Gamepad {
         self.ff_supported
     }
 
+    pub fn ff_capabilities(&self) -> Capabilities {
+        self.ff_capabilities.clone()
+    }
+
+    pub fn ff_motors(&self) -> &[Motor] {
+        if self.ff_supported {
+            &[Motor::Strong, Motor::Weak]
+        } else {
+            &[]
+        }
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -756,7 +1043,7 @@ unsafe fn cstr_new(bytes: &[u8]) -> &CStr {
 use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct EvCode {
     kind: u16,
     code: u16,
@@ -767,6 +1054,14 @@ impl EvCode {
         EvCode { kind, code }
     }
 
+    pub(super) fn kind(self) -> u16 {
+        self.kind
+    }
+
+    pub(super) fn code(self) -> u16 {
+        self.code
+    }
+
     pub fn into_u32(self) -> u32 {
         u32::from(self.kind) << 16 | u32::from(self.code)
     }
@@ -868,12 +1163,6 @@ const ABS_HAT1Y: u16 = 0x13;
 const ABS_HAT2X: u16 = 0x14;
 const ABS_HAT2Y: u16 = 0x15;
 
-const FF_MAX: u16 = FF_GAIN;
-const FF_SQUARE: u16 = 0x58;
-const FF_TRIANGLE: u16 = 0x59;
-const FF_SINE: u16 = 0x5a;
-const FF_GAIN: u16 = 0x60;
-
 pub mod native_ev_codes {
     use super::*;
 
@@ -9,7 +9,10 @@
 // Diffing linux/input.h across different architectures (i686, x86_64 and arm) didn't show any
 // difference, so it looks like conditional compilation is not needed.
 
-use nix::{ioctl_read, ioctl_read_buf, ioctl_write_int, ioctl_write_ptr, request_code_read};
+use nix::{
+    ioctl_none, ioctl_read, ioctl_read_buf, ioctl_readwrite, ioctl_write_int, ioctl_write_ptr,
+    request_code_read,
+};
 use std::mem::MaybeUninit;
 
 #[cfg(target_env = "musl")]
@@ -22,6 +25,19 @@ ioctl_write_int!(eviocrmff, b'E', 0x81);
 ioctl_write_ptr!(eviocsff, b'E', 0x80, ff_effect);
 ioctl_read_buf!(eviocgname, b'E', 0x06, MaybeUninit<u8>);
 ioctl_read_buf!(eviocgkey, b'E', 0x18, u8);
+ioctl_read!(eviocgeffects, b'E', 0x84, libc::c_int);
+
+ioctl_none!(ui_dev_create, b'U', 1);
+ioctl_none!(ui_dev_destroy, b'U', 2);
+ioctl_write_int!(ui_set_evbit, b'U', 100);
+ioctl_write_int!(ui_set_keybit, b'U', 101);
+ioctl_write_int!(ui_set_absbit, b'U', 103);
+ioctl_write_int!(ui_set_ffbit, b'U', 107);
+ioctl_read_buf!(ui_get_sysname, b'U', 44, u8);
+ioctl_readwrite!(ui_begin_ff_upload, b'U', 200, uinput_ff_upload);
+ioctl_write_ptr!(ui_end_ff_upload, b'U', 201, uinput_ff_upload);
+ioctl_readwrite!(ui_begin_ff_erase, b'U', 202, uinput_ff_erase);
+ioctl_write_ptr!(ui_end_ff_erase, b'U', 203, uinput_ff_erase);
 
 pub unsafe fn eviocgbit(fd: libc::c_int, ev: u32, len: libc::c_int, buf: *mut u8) -> libc::c_int {
     ::nix::libc::ioctl(
@@ -65,7 +81,7 @@ impl ::std::fmt::Debug for input_event {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct input_id {
     pub bustype: u16,
@@ -172,3 +188,47 @@ pub struct ff_effect {
     #[cfg(target_pointer_width = "32")]
     pub u: [u32; 7],
 }
+
+pub const UINPUT_MAX_NAME_SIZE: usize = 80;
+pub const ABS_CNT: usize = 0x40;
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct uinput_user_dev {
+    pub name: [u8; UINPUT_MAX_NAME_SIZE],
+    pub id: input_id,
+    pub ff_effects_max: u32,
+    pub absmax: [i32; ABS_CNT],
+    pub absmin: [i32; ABS_CNT],
+    pub absfuzz: [i32; ABS_CNT],
+    pub absflat: [i32; ABS_CNT],
+}
+
+impl ::std::default::Default for uinput_user_dev {
+    fn default() -> Self {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct uinput_ff_upload {
+    pub request_id: u32,
+    pub retval: i32,
+    pub effect: ff_effect,
+    pub old: ff_effect,
+}
+
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+pub struct uinput_ff_erase {
+    pub request_id: u32,
+    pub retval: i32,
+    pub effect_id: u32,
+}
+
+/// Converts error returned by ioctl to `io::Error`.
+pub fn nix_to_io(err: nix::Error) -> std::io::Error {
+    // ioctls only fail with errno
+    std::io::Error::from_raw_os_error(err.as_errno().map_or(libc::EINVAL, |errno| errno as i32))
+}
@@ -9,8 +9,9 @@ mod ff;
 mod gamepad;
 mod ioctl;
 mod udev;
+pub mod uinput;
 
 pub use self::ff::Device as FfDevice;
-pub use self::gamepad::{native_ev_codes, EvCode, Gamepad, Gilrs};
+pub use self::gamepad::{native_ev_codes, EvCode, FdChange, Gamepad, Gilrs, Waiter};
 
 pub const IS_Y_AXIS_REVERSED: bool = true;
@@ -236,6 +236,10 @@ impl Monitor {
     pub fn device(&self) -> Device {
         Device(unsafe { ud::udev_monitor_receive_device(self.0) })
     }
+
+    pub fn fd(&self) -> c::c_int {
+        unsafe { ud::udev_monitor_get_fd(self.0) }
+    }
 }
 
 impl Drop for Monitor {
@@ -0,0 +1,554 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Evdev devices created with `/dev/uinput` (Linux only).
+//!
+//! Unlike [`virtual_gamepad`](../virtual_gamepad/index.html), devices created with this module are
+//! visible to the whole system. They are detected by udev and opened by `Gilrs` (and any other
+//! program) like physical gamepads, so they can be used to test hotplug and force feedback or to
+//! re-expose modified input of another device.
+//!
+//! Creating devices usually requires write access to `/dev/uinput`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use gilrs_core::native_ev_codes as nec;
+//! use gilrs_core::uinput::{FfEvent, UinputBuilder};
+//! use gilrs_core::AxisInfo;
+//!
+//! let mut device = UinputBuilder::new("Test gamepad")
+//!     .button(nec::BTN_SOUTH)
+//!     .axis(nec::AXIS_LSTICKX, AxisInfo { min: -32768, max: 32767, deadzone: Some(128) })
+//!     .ff_rumble(16)
+//!     .create()
+//!     .unwrap();
+//!
+//! device.press(nec::BTN_SOUTH).unwrap();
+//! device.set_axis(nec::AXIS_LSTICKX, 1000).unwrap();
+//!
+//! while let Some(ev) = device.next_ff_event() {
+//!     if let FfEvent::Play { id, .. } = ev {
+//!         println!("Effect {} started", id);
+//!     }
+//! }
+//! ```
+
+use super::ioctl::{self, input_event, input_id, nix_to_io, uinput_ff_erase, uinput_ff_upload};
+use super::ioctl::{ff_rumble_effect, uinput_user_dev, ABS_CNT, UINPUT_MAX_NAME_SIZE};
+use crate::{AxisInfo, EvCode};
+
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, ErrorKind, Read, Result as IoResult, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+use std::{mem, slice};
+
+/// Description of uinput device.
+#[derive(Clone, Debug)]
+pub struct UinputBuilder {
+    name: String,
+    id: input_id,
+    buttons: Vec<super::EvCode>,
+    axes: Vec<(super::EvCode, AxisInfo)>,
+    ff_effects_max: u32,
+    ff_gain: bool,
+    ff_autocenter: bool,
+}
+
+impl UinputBuilder {
+    /// Creates description of device without any buttons and axes. Name is truncated to 79 bytes.
+    pub fn new(name: &str) -> Self {
+        UinputBuilder {
+            name: name.to_owned(),
+            id: input_id {
+                bustype: BUS_VIRTUAL,
+                vendor: 0,
+                product: 0,
+                version: 0,
+            },
+            buttons: Vec::new(),
+            axes: Vec::new(),
+            ff_effects_max: 0,
+            ff_gain: false,
+            ff_autocenter: false,
+        }
+    }
+
+    /// Sets bus type, vendor, product and version reported by device. These values are used to
+    /// create UUID of gamepad, so they decide which SDL mapping will be used.
+    ///
+    /// Defaults to `BUS_VIRTUAL` and zeros.
+    pub fn ids(mut self, bustype: u16, vendor: u16, product: u16, version: u16) -> Self {
+        self.id = input_id {
+            bustype,
+            vendor,
+            product,
+            version,
+        };
+
+        self
+    }
+
+    /// Adds button. `create()` will fail if `code` is not a `EV_KEY` code.
+    pub fn button<C: Into<EvCode>>(mut self, code: C) -> Self {
+        let code = code.into().0;
+        if !self.buttons.contains(&code) {
+            self.buttons.push(code);
+        }
+
+        self
+    }
+
+    /// Adds axis with given range. `AxisInfo::deadzone` is reported as flat value of axis. If
+    /// axis was already added, only its `AxisInfo` is changed.
+    ///
+    /// `create()` will fail if `code` is not a `EV_ABS` code.
+    pub fn axis<C: Into<EvCode>>(mut self, code: C, info: AxisInfo) -> Self {
+        let code = code.into().0;
+        match self.axes.iter().position(|&(c, _)| c == code) {
+            Some(idx) => self.axes[idx].1 = info,
+            None => self.axes.push((code, info)),
+        }
+
+        self
+    }
+
+    /// Enables `FF_RUMBLE` effects. `max_effects` is number of effects that can be uploaded at
+    /// the same time. Force feedback is disabled if it's 0 (default).
+    pub fn ff_rumble(mut self, max_effects: u32) -> Self {
+        self.ff_effects_max = max_effects;
+
+        self
+    }
+
+    /// Enables `FF_GAIN`. Changes of gain are returned as `FfEvent::Gain`. Has no effect if
+    /// `FF_RUMBLE` is disabled.
+    pub fn ff_gain(mut self) -> Self {
+        self.ff_gain = true;
+
+        self
+    }
+
+    /// Enables `FF_AUTOCENTER`. Changes of autocenter strength are returned as
+    /// `FfEvent::Autocenter`. Has no effect if `FF_RUMBLE` is disabled.
+    pub fn ff_autocenter(mut self) -> Self {
+        self.ff_autocenter = true;
+
+        self
+    }
+
+    /// Creates device.
+    pub fn create(&self) -> IoResult<UinputDevice> {
+        let invalid = |msg| Err(IoError::new(ErrorKind::InvalidInput, msg));
+
+        if self
+            .buttons
+            .iter()
+            .any(|b| b.kind() != EV_KEY || b.code() > KEY_MAX)
+        {
+            return invalid("button code is not a valid EV_KEY code");
+        }
+
+        if self
+            .axes
+            .iter()
+            .any(|(a, _)| a.kind() != EV_ABS || a.code() as usize >= ABS_CNT)
+        {
+            return invalid("axis code is not a valid EV_ABS code");
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")?;
+        let fd = file.as_raw_fd();
+
+        let mut dev = uinput_user_dev::default();
+        let name = self.name.as_bytes();
+        let len = name.len().min(UINPUT_MAX_NAME_SIZE - 1);
+        dev.name[..len].copy_from_slice(&name[..len]);
+        dev.id = self.id;
+        dev.ff_effects_max = self.ff_effects_max;
+
+        unsafe {
+            if !self.buttons.is_empty() {
+                ioctl::ui_set_evbit(fd, EV_KEY.into()).map_err(nix_to_io)?;
+            }
+
+            for btn in &self.buttons {
+                ioctl::ui_set_keybit(fd, btn.code().into()).map_err(nix_to_io)?;
+            }
+
+            if !self.axes.is_empty() {
+                ioctl::ui_set_evbit(fd, EV_ABS.into()).map_err(nix_to_io)?;
+            }
+
+            for &(abs, info) in &self.axes {
+                ioctl::ui_set_absbit(fd, abs.code().into()).map_err(nix_to_io)?;
+
+                let abs = abs.code() as usize;
+                dev.absmin[abs] = info.min;
+                dev.absmax[abs] = info.max;
+                dev.absflat[abs] = info.deadzone.unwrap_or(0) as i32;
+            }
+
+            if self.ff_effects_max > 0 {
+                ioctl::ui_set_evbit(fd, EV_FF.into()).map_err(nix_to_io)?;
+                ioctl::ui_set_ffbit(fd, FF_RUMBLE.into()).map_err(nix_to_io)?;
+
+                if self.ff_gain {
+                    ioctl::ui_set_ffbit(fd, FF_GAIN.into()).map_err(nix_to_io)?;
+                }
+
+                if self.ff_autocenter {
+                    ioctl::ui_set_ffbit(fd, FF_AUTOCENTER.into()).map_err(nix_to_io)?;
+                }
+            }
+        }
+
+        let size = mem::size_of::<uinput_user_dev>();
+        let s = unsafe { slice::from_raw_parts(&dev as *const _ as *const u8, size) };
+        (&file).write_all(s)?;
+
+        unsafe { ioctl::ui_dev_create(fd) }.map_err(nix_to_io)?;
+
+        Ok(UinputDevice { file })
+    }
+}
+
+/// Force feedback request sent to uinput device by program that opened it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum FfEvent {
+    /// Rumble effect was uploaded or modified.
+    Upload {
+        id: i16,
+        strong: u16,
+        weak: u16,
+        length: Duration,
+    },
+    /// Effect was removed.
+    Erase { id: i16 },
+    /// Effect should be played `count` times.
+    Play { id: i16, count: i32 },
+    /// Effect should be stopped.
+    Stop { id: i16 },
+    /// Gain of device was changed. `u16::MAX` is full strength.
+    Gain { gain: u16 },
+    /// Strength of autocenter spring was changed. 0 disables autocenter.
+    Autocenter { strength: u16 },
+}
+
+/// Evdev device created with `/dev/uinput`. Device is destroyed when this object is dropped.
+#[derive(Debug)]
+pub struct UinputDevice {
+    file: File,
+}
+
+impl UinputDevice {
+    /// Queues input event. Events are not visible to readers of device until `sync()` is called.
+    pub fn emit<C: Into<EvCode>>(&mut self, code: C, value: i32) -> IoResult<()> {
+        let code = code.into().0;
+        self.write_event(code.kind(), code.code(), value)
+    }
+
+    /// Sends `SYN_REPORT`, which makes all queued events visible to readers of device.
+    pub fn sync(&mut self) -> IoResult<()> {
+        self.write_event(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// Presses button and calls `sync()`.
+    pub fn press<C: Into<EvCode>>(&mut self, code: C) -> IoResult<()> {
+        self.emit(code, 1)?;
+        self.sync()
+    }
+
+    /// Releases button and calls `sync()`.
+    pub fn release<C: Into<EvCode>>(&mut self, code: C) -> IoResult<()> {
+        self.emit(code, 0)?;
+        self.sync()
+    }
+
+    /// Changes value of axis and calls `sync()`.
+    pub fn set_axis<C: Into<EvCode>>(&mut self, code: C, value: i32) -> IoResult<()> {
+        self.emit(code, value)?;
+        self.sync()
+    }
+
+    /// Returns name of device in sysfs, e.g. `input42`. Device's directory is
+    /// `/sys/devices/virtual/input/<sysname>`.
+    pub fn sysname(&self) -> IoResult<String> {
+        let mut buf = [0u8; 64];
+        unsafe { ioctl::ui_get_sysname(self.file.as_raw_fd(), &mut buf) }.map_err(nix_to_io)?;
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+
+        Ok(String::from_utf8_lossy(&buf[..len]).into_owned())
+    }
+
+    /// Returns next pending force feedback request. This function does not block.
+    ///
+    /// Upload and erase requests are acknowledged before they are returned. Program that uploads
+    /// or removes effect is blocked until this happens, so this function should be called
+    /// regularly when force feedback is enabled.
+    pub fn next_ff_event(&mut self) -> Option<FfEvent> {
+        loop {
+            let ev = self.read_event()?;
+
+            match (ev.type_, ev.code) {
+                (EV_UINPUT, UI_FF_UPLOAD) => {
+                    if let Some(ev) = self.handle_upload(ev.value as u32) {
+                        return Some(ev);
+                    }
+                }
+                (EV_UINPUT, UI_FF_ERASE) => {
+                    if let Some(ev) = self.handle_erase(ev.value as u32) {
+                        return Some(ev);
+                    }
+                }
+                (EV_FF, FF_GAIN) => {
+                    return Some(FfEvent::Gain {
+                        gain: ev.value as u16,
+                    })
+                }
+                (EV_FF, FF_AUTOCENTER) => {
+                    return Some(FfEvent::Autocenter {
+                        strength: ev.value as u16,
+                    })
+                }
+                (EV_FF, id) if ev.value > 0 => {
+                    return Some(FfEvent::Play {
+                        id: id as i16,
+                        count: ev.value,
+                    })
+                }
+                (EV_FF, id) => return Some(FfEvent::Stop { id: id as i16 }),
+                _ => (),
+            }
+        }
+    }
+
+    fn handle_upload(&mut self, request_id: u32) -> Option<FfEvent> {
+        let fd = self.file.as_raw_fd();
+        let mut upload: uinput_ff_upload = unsafe { mem::zeroed() };
+        upload.request_id = request_id;
+
+        if let Err(err) = unsafe { ioctl::ui_begin_ff_upload(fd, &mut upload) } {
+            error!("Failed to begin uploading effect: {}", err);
+            return None;
+        }
+
+        let ev = if upload.effect.type_ == FF_RUMBLE {
+            let rumble = unsafe { *(&upload.effect.u as *const _ as *const ff_rumble_effect) };
+            upload.retval = 0;
+
+            Some(FfEvent::Upload {
+                id: upload.effect.id,
+                strong: rumble.strong_magnitude,
+                weak: rumble.weak_magnitude,
+                length: Duration::from_millis(upload.effect.replay.length.into()),
+            })
+        } else {
+            upload.retval = -libc::EINVAL;
+
+            None
+        };
+
+        if let Err(err) = unsafe { ioctl::ui_end_ff_upload(fd, &upload) } {
+            error!("Failed to end uploading effect: {}", err);
+        }
+
+        ev
+    }
+
+    fn handle_erase(&mut self, request_id: u32) -> Option<FfEvent> {
+        let fd = self.file.as_raw_fd();
+        let mut erase = uinput_ff_erase {
+            request_id,
+            ..Default::default()
+        };
+
+        if let Err(err) = unsafe { ioctl::ui_begin_ff_erase(fd, &mut erase) } {
+            error!("Failed to begin removing effect: {}", err);
+            return None;
+        }
+
+        erase.retval = 0;
+
+        if let Err(err) = unsafe { ioctl::ui_end_ff_erase(fd, &erase) } {
+            error!("Failed to end removing effect: {}", err);
+        }
+
+        Some(FfEvent::Erase {
+            id: erase.effect_id as i16,
+        })
+    }
+
+    fn read_event(&mut self) -> Option<input_event> {
+        let mut ev = input_event::default();
+        let size = mem::size_of::<input_event>();
+        let s = unsafe { slice::from_raw_parts_mut(&mut ev as *mut _ as *mut u8, size) };
+
+        match self.file.read(s) {
+            Ok(n) if n == size => Some(ev),
+            Ok(n) => {
+                error!("Unexpected read of size {}", n);
+                None
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => None,
+            Err(e) => {
+                error!("Failed to read uinput event: {}", e);
+                None
+            }
+        }
+    }
+
+    fn write_event(&mut self, type_: u16, code: u16, value: i32) -> IoResult<()> {
+        let ev = input_event {
+            time: libc::timeval {
+                tv_sec: 0,
+                tv_usec: 0,
+            },
+            type_,
+            code,
+            value,
+        };
+
+        let size = mem::size_of::<input_event>();
+        let s = unsafe { slice::from_raw_parts(&ev as *const _ as *const u8, size) };
+
+        self.file.write_all(s)
+    }
+}
+
+impl Drop for UinputDevice {
+    fn drop(&mut self) {
+        if let Err(err) = unsafe { ioctl::ui_dev_destroy(self.file.as_raw_fd()) } {
+            error!("Failed to destroy uinput device: {}", err);
+        }
+    }
+}
+
+const BUS_VIRTUAL: u16 = 0x06;
+
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const EV_FF: u16 = 0x15;
+const EV_UINPUT: u16 = 0x0101;
+
+const SYN_REPORT: u16 = 0x00;
+const KEY_MAX: u16 = 0x2ff;
+
+const FF_RUMBLE: u16 = 0x50;
+const FF_GAIN: u16 = 0x60;
+const FF_AUTOCENTER: u16 = 0x61;
+
+const UI_FF_UPLOAD: u16 = 1;
+const UI_FF_ERASE: u16 = 2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff::{Effect, EffectKind};
+    use crate::native_ev_codes as nec;
+    use crate::{EventType, Gilrs};
+
+    use std::thread;
+    use std::time::Instant;
+
+    /// Requires write access to `/dev/uinput` and udev, run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn round_trip() {
+        let name = "gilrs uinput round trip";
+        let mut device = UinputBuilder::new(name)
+            .button(nec::BTN_SOUTH)
+            .axis(
+                nec::AXIS_LSTICKX,
+                AxisInfo {
+                    min: -32768,
+                    max: 32767,
+                    deadzone: None,
+                },
+            )
+            .ff_rumble(4)
+            .ff_gain()
+            .create()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut gilrs = Gilrs::new().unwrap();
+        let id = loop {
+            let found = (0..gilrs.last_gamepad_hint()).find(|&id| match gilrs.gamepad(id) {
+                Some(gp) => gp.is_connected() && gp.name() == name,
+                None => false,
+            });
+            if let Some(id) = found {
+                break id;
+            }
+            assert!(Instant::now() < deadline, "device was not detected");
+            gilrs.next_event_blocking(Some(Duration::from_millis(100)));
+        };
+        while gilrs.next_event().is_some() {}
+
+        device.press(nec::BTN_SOUTH).unwrap();
+        let ev = gilrs
+            .next_event_blocking(Some(Duration::from_secs(1)))
+            .unwrap();
+        assert_eq!(
+            (ev.id, ev.event),
+            (id, EventType::ButtonPressed(nec::BTN_SOUTH))
+        );
+
+        let gamepad = gilrs.gamepad(id).unwrap();
+        assert!(gamepad.ff_capabilities().gain);
+        let mut ff = gamepad.ff_device().unwrap();
+        // Uploading blocks until the request is handled, so it can't be done on this thread.
+        let client = thread::spawn(move || {
+            let effect = Effect::new(EffectKind::Rumble {
+                strong: 0x8000,
+                weak: 0x4000,
+            })
+            .length(Duration::from_millis(500));
+            let id = ff.upload_effect(&effect).unwrap();
+            ff.play_effect(id, 1).unwrap();
+            ff.set_gain(0x1000).unwrap();
+        });
+
+        let mut events = Vec::new();
+        while events.len() < 3 && Instant::now() < deadline {
+            match device.next_ff_event() {
+                Some(ev) => events.push(ev),
+                None => thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        client.join().unwrap();
+
+        let effect_id = match events[0] {
+            FfEvent::Upload {
+                id,
+                strong: 0x8000,
+                weak: 0x4000,
+                length,
+            } if length == Duration::from_millis(500) => id,
+            ev => panic!("unexpected event {:?}", ev),
+        };
+        assert_eq!(
+            events[1..],
+            [
+                FfEvent::Play {
+                    id: effect_id,
+                    count: 1
+                },
+                FfEvent::Gain { gain: 0x1000 },
+            ]
+        );
+    }
+}
@@ -5,6 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::ff::{Effect, EffectId, Motor};
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -14,4 +16,41 @@ pub struct Device;
 impl Device {
     /// Sets magnitude for strong and weak ff motors.
     pub fn set_ff_state(&mut self, _strong: u16, _weak: u16, _min_duration: Duration) {}
+
+    pub fn set_motors(&mut self, _magnitudes: &[(Motor, u16)], _min_duration: Duration) {}
+
+    pub fn upload_effect(&mut self, _effect: &Effect) -> IoResult<EffectId> {
+        Err(unsupported())
+    }
+
+    pub fn update_effect(&mut self, _id: EffectId, _effect: &Effect) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn play_effect(&mut self, _id: EffectId, _count: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn stop_effect(&mut self, _id: EffectId) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn erase_effect(&mut self, _id: EffectId) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn set_gain(&mut self, _gain: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn set_autocenter(&mut self, _strength: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> IoError {
+    IoError::new(
+        ErrorKind::Other,
+        "force feedback effects are not supported on this platform",
+    )
 }
@@ -7,6 +7,7 @@
 
 use super::io_kit::*;
 use super::FfDevice;
+use crate::ff::{Capabilities, Motor};
 use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
 use uuid::Uuid;
 
@@ -24,6 +25,7 @@ use std::os::raw::c_void;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct Gilrs {
@@ -78,7 +80,24 @@ impl Gilrs {
     }
 
     pub(crate) fn next_event(&mut self) -> Option<Event> {
-        match self.rx.try_recv().ok() {
+        let ev = self.rx.try_recv().ok();
+        self.handle_event(ev)
+    }
+
+    pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        let ev = match timeout {
+            Some(timeout) => self.rx.recv_timeout(timeout).ok(),
+            None => self.rx.recv().ok(),
+        };
+        self.handle_event(ev)
+    }
+
+    pub fn waiter(&self) -> Waiter {
+        Waiter
+    }
+
+    fn handle_event(&mut self, ev: Option<(Event, Option<IOHIDDevice>)>) -> Option<Event> {
+        match ev {
             Some((event, Some(device))) => {
                 if event.event == EventType::Connected {
                     if self.gamepads.get(event.id).is_some() {
@@ -132,6 +151,22 @@ impl Gilrs {
     }
 }
 
+/// Waits for new events from backend.
+///
+/// Events are delivered through a channel owned by `Gilrs`, so this only sleeps for a short time
+/// and reports that new events may be available.
+#[derive(Debug)]
+pub struct Waiter;
+
+impl Waiter {
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let sleep = Duration::from_millis(10);
+        thread::sleep(timeout.map_or(sleep, |timeout| timeout.min(sleep)));
+
+        true
+    }
+}
+
 #[derive(Debug)]
 pub struct Gamepad {
     name: String,
@@ -303,6 +338,14 @@ impl Gamepad {
         false
     }
 
+    pub fn ff_motors(&self) -> &[Motor] {
+        &[]
+    }
+
+    pub fn ff_capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Creates Ffdevice corresponding to this gamepad.
     pub fn ff_device(&self) -> Option<FfDevice> {
         Some(FfDevice)
@@ -405,7 +448,7 @@ struct DeviceInfo {
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct EvCode {
     page: u32,
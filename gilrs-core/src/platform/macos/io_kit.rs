@@ -9,8 +9,8 @@
 #![allow(non_snake_case)]
 
 use core_foundation::array::{
-    kCFTypeArrayCallBacks, CFArray, CFArrayCallBacks, CFArrayGetCount, CFArrayGetValueAtIndex,
-    __CFArray,
+    __CFArray, kCFTypeArrayCallBacks, CFArray, CFArrayCallBacks, CFArrayGetCount,
+    CFArrayGetValueAtIndex,
 };
 use core_foundation::base::{
     kCFAllocatorDefault, CFAllocatorRef, CFIndex, CFRelease, CFType, TCFType,
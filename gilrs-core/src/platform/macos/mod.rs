@@ -9,7 +9,7 @@ mod gamepad;
 mod io_kit;
 
 pub use self::ff::Device as FfDevice;
-pub use self::gamepad::{native_ev_codes, EvCode, Gamepad, Gilrs};
+pub use self::gamepad::{native_ev_codes, EvCode, Gamepad, Gilrs, Waiter};
 
 // True, if Y axis of sticks points downwards.
 pub const IS_Y_AXIS_REVERSED: bool = true;
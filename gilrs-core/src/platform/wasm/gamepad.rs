@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::FfDevice;
+use crate::ff::{Capabilities, Motor};
 use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
 use uuid::Uuid;
 
@@ -14,6 +15,7 @@ use stdweb::web::{Gamepad as WebGamepad, GamepadMappingType};
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::i32::MAX as I32_MAX;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct Gilrs {
@@ -114,6 +116,15 @@ impl Gilrs {
         self.event_cache.pop_front()
     }
 
+    pub(crate) fn next_event_blocking(&mut self, _timeout: Option<Duration>) -> Option<Event> {
+        // Blocking is not possible in browser.
+        self.next_event()
+    }
+
+    pub fn waiter(&self) -> Waiter {
+        Waiter
+    }
+
     pub fn gamepad(&self, id: usize) -> Option<&Gamepad> {
         self.gamepads.get(id)
     }
@@ -147,6 +158,17 @@ impl Mapping {
     }
 }
 
+/// Waits for new events from backend.
+#[derive(Debug)]
+pub struct Waiter;
+
+impl Waiter {
+    pub fn wait(&self, _timeout: Option<Duration>) -> bool {
+        // Blocking is not possible in browser.
+        true
+    }
+}
+
 #[derive(Debug)]
 pub struct Gamepad {
     uuid: Uuid,
@@ -222,6 +244,14 @@ impl Gamepad {
         false
     }
 
+    pub fn ff_motors(&self) -> &[Motor] {
+        &[]
+    }
+
+    pub fn ff_capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     pub fn ff_device(&self) -> Option<FfDevice> {
         None
     }
@@ -259,7 +289,7 @@ impl Gamepad {
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct EvCode(u8);
 
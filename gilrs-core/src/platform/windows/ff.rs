@@ -5,7 +5,9 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::ff::{Effect, EffectId, Motor};
 use rusty_xinput::{self, XInputUsageError};
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -35,4 +37,54 @@ impl Device {
             }
         }
     }
+
+    pub fn set_motors(&mut self, magnitudes: &[(Motor, u16)], min_duration: Duration) {
+        let magnitude = |motor| {
+            magnitudes
+                .iter()
+                .filter(|&&(m, _)| m == motor)
+                .fold(0u16, |acc, &(_, mg)| acc.saturating_add(mg))
+        };
+
+        self.set_ff_state(
+            magnitude(Motor::Strong),
+            magnitude(Motor::Weak),
+            min_duration,
+        );
+    }
+
+    pub fn upload_effect(&mut self, _effect: &Effect) -> IoResult<EffectId> {
+        Err(unsupported())
+    }
+
+    pub fn update_effect(&mut self, _id: EffectId, _effect: &Effect) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn play_effect(&mut self, _id: EffectId, _count: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn stop_effect(&mut self, _id: EffectId) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn erase_effect(&mut self, _id: EffectId) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn set_gain(&mut self, _gain: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn set_autocenter(&mut self, _strength: u16) -> IoResult<()> {
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> IoError {
+    IoError::new(
+        ErrorKind::Other,
+        "force feedback effects are not supported on this platform",
+    )
 }
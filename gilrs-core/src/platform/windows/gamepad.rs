@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::FfDevice;
+use crate::ff::{Capabilities, Motor};
 use crate::{AxisInfo, Event, EventType, PlatformError, PowerInfo};
 
 use std::error::Error as StdError;
@@ -64,7 +65,22 @@ impl Gilrs {
 
     pub(crate) fn next_event(&mut self) -> Option<Event> {
         let ev = self.rx.try_recv().ok();
+        self.handle_event(ev)
+    }
 
+    pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        let ev = match timeout {
+            Some(timeout) => self.rx.recv_timeout(timeout).ok(),
+            None => self.rx.recv().ok(),
+        };
+        self.handle_event(ev)
+    }
+
+    pub fn waiter(&self) -> Waiter {
+        Waiter
+    }
+
+    fn handle_event(&mut self, ev: Option<Event>) -> Option<Event> {
         if let Some(ev) = ev {
             match ev.event {
                 EventType::Connected => self.gamepads[ev.id].is_connected = true,
@@ -356,6 +372,22 @@ impl Gilrs {
     }
 }
 
+/// Waits for new events from backend.
+///
+/// Events are delivered through a channel owned by `Gilrs`, so this only sleeps for a short time
+/// and reports that new events may be available.
+#[derive(Debug)]
+pub struct Waiter;
+
+impl Waiter {
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let sleep = Duration::from_millis(10);
+        thread::sleep(timeout.map_or(sleep, |timeout| timeout.min(sleep)));
+
+        true
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Gamepad {
     uuid: Uuid,
@@ -430,6 +462,14 @@ impl Gamepad {
         true
     }
 
+    pub fn ff_motors(&self) -> &[Motor] {
+        &[Motor::Strong, Motor::Weak]
+    }
+
+    pub fn ff_capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     pub fn ff_device(&self) -> Option<FfDevice> {
         Some(FfDevice::new(self.id))
     }
@@ -457,7 +497,7 @@ fn is_mask_eq(l: u16, r: u16, mask: u16) -> bool {
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature="serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct EvCode(u8);
 
@@ -0,0 +1,485 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Gamepads created from code.
+//!
+//! Virtual backend does not use any device. Gamepads are added and their input is injected with
+//! [`Injector`](struct.Injector.html) returned by
+//! [`Gilrs::new_virtual()`](../struct.Gilrs.html#method.new_virtual). Events are then returned
+//! from `Gilrs::next_event()` exactly like events from real devices, which makes it possible to
+//! test code that depends on gamepad input on machines without any controllers.
+//!
+//! # Example
+//!
+//! ```
+//! use gilrs_core::native_ev_codes as nec;
+//! use gilrs_core::virtual_gamepad::VirtualGamepad;
+//! use gilrs_core::{AxisInfo, EventType, Gilrs};
+//!
+//! let (mut gilrs, mut injector) = Gilrs::new_virtual();
+//! let id = injector.add_gamepad(
+//!     VirtualGamepad::new("Test gamepad")
+//!         .button(nec::BTN_SOUTH)
+//!         .axis(nec::AXIS_LSTICKX, AxisInfo { min: -128, max: 127, deadzone: None }),
+//! );
+//! injector.press(id, nec::BTN_SOUTH);
+//!
+//! assert_eq!(gilrs.next_event().unwrap().event, EventType::Connected);
+//! assert_eq!(gilrs.next_event().unwrap().event, EventType::ButtonPressed(nec::BTN_SOUTH));
+//! assert!(gilrs.next_event().is_none());
+//! ```
+
+use crate::platform;
+use crate::{AxisInfo, EvCode, Event, EventType, PowerInfo};
+
+use uuid::Uuid;
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Description of virtual gamepad.
+///
+/// Gamepad will only emit events for buttons and axes that were added to it.
+#[derive(Clone, Debug)]
+pub struct VirtualGamepad {
+    name: String,
+    uuid: Uuid,
+    power_info: PowerInfo,
+    buttons: Vec<platform::EvCode>,
+    axes: Vec<platform::EvCode>,
+    axes_info: Vec<AxisInfo>,
+}
+
+impl VirtualGamepad {
+    /// Creates description of gamepad without any buttons and axes. UUID defaults to nil UUID
+    /// and power info to `PowerInfo::Wired`.
+    pub fn new(name: &str) -> Self {
+        VirtualGamepad {
+            name: name.to_owned(),
+            uuid: Uuid::nil(),
+            power_info: PowerInfo::Wired,
+            buttons: Vec::new(),
+            axes: Vec::new(),
+            axes_info: Vec::new(),
+        }
+    }
+
+    /// Sets UUID reported by gamepad. It is used to find SDL mappings, so it should be in the
+    /// same format as UUID of real device.
+    pub fn uuid(mut self, uuid: [u8; 16]) -> Self {
+        self.uuid = Uuid::from_bytes(uuid);
+
+        self
+    }
+
+    /// Sets power supply state reported by gamepad.
+    pub fn power_info(mut self, power_info: PowerInfo) -> Self {
+        self.power_info = power_info;
+
+        self
+    }
+
+    /// Adds button.
+    pub fn button<C: Into<EvCode>>(mut self, code: C) -> Self {
+        let code = code.into().0;
+        if !self.buttons.contains(&code) {
+            self.buttons.push(code);
+        }
+
+        self
+    }
+
+    /// Adds axis with given range and deadzone. If axis was already added, only its `AxisInfo`
+    /// is changed.
+    pub fn axis<C: Into<EvCode>>(mut self, code: C, info: AxisInfo) -> Self {
+        let code = code.into().0;
+        match self.axes.iter().position(|&c| c == code) {
+            Some(idx) => self.axes_info[idx] = info,
+            None => {
+                self.axes.push(code);
+                self.axes_info.push(info);
+            }
+        }
+
+        self
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Add(VirtualGamepad),
+    Event(Event),
+}
+
+/// Number of messages that were sent, but not received yet. Shared between `Injector`, `Gilrs`
+/// and `Waiter`s, so waiting for events doesn't require access to the channel.
+#[derive(Debug, Default)]
+struct Queued {
+    count: Mutex<usize>,
+    cond: Condvar,
+}
+
+/// Adds virtual gamepads and injects their events.
+///
+/// Events are delivered in the same order they were injected. Input events for unknown gamepads,
+/// disconnected gamepads or elements that gamepad doesn't have are dropped.
+#[derive(Debug)]
+pub struct Injector {
+    tx: Sender<Message>,
+    queued: Arc<Queued>,
+    next_id: usize,
+}
+
+impl Injector {
+    /// Adds new gamepad and returns its ID. `EventType::Connected` will be emitted for it.
+    pub fn add_gamepad(&mut self, gamepad: VirtualGamepad) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send_message(Message::Add(gamepad));
+
+        id
+    }
+
+    /// Injects event. Its `time` is preserved.
+    ///
+    /// `EventType::Connected` and `EventType::Disconnected` change connection state of gamepad
+    /// with `event.id`.
+    pub fn send(&self, event: Event) {
+        self.send_message(Message::Event(event));
+    }
+
+    fn send_message(&self, msg: Message) {
+        // Count has to be incremented before message can be received, otherwise receiver could
+        // decrement it first and it would stay nonzero with empty queue.
+        let mut count = self.queued.count.lock().unwrap();
+        if self.tx.send(msg).is_ok() {
+            *count += 1;
+            drop(count);
+            self.queued.cond.notify_all();
+        }
+    }
+
+    /// Injects `ButtonPressed` event with current time.
+    pub fn press<C: Into<EvCode>>(&self, id: usize, code: C) {
+        self.send(Event::new(id, EventType::ButtonPressed(code.into())));
+    }
+
+    /// Injects `ButtonReleased` event with current time.
+    pub fn release<C: Into<EvCode>>(&self, id: usize, code: C) {
+        self.send(Event::new(id, EventType::ButtonReleased(code.into())));
+    }
+
+    /// Injects `AxisValueChanged` event with current time.
+    pub fn set_axis<C: Into<EvCode>>(&self, id: usize, code: C, value: i32) {
+        self.send(Event::new(
+            id,
+            EventType::AxisValueChanged(value, code.into()),
+        ));
+    }
+
+    /// Reconnects previously disconnected gamepad.
+    pub fn connect(&self, id: usize) {
+        self.send(Event::new(id, EventType::Connected));
+    }
+
+    /// Disconnects gamepad.
+    pub fn disconnect(&self, id: usize) {
+        self.send(Event::new(id, EventType::Disconnected));
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Gilrs {
+    gamepads: Vec<Gamepad>,
+    rx: Receiver<Message>,
+    queued: Arc<Queued>,
+}
+
+impl Gilrs {
+    pub(crate) fn new() -> (Self, Injector) {
+        let (tx, rx) = mpsc::channel();
+        let queued = Arc::new(Queued::default());
+
+        (
+            Gilrs {
+                gamepads: Vec::new(),
+                rx,
+                queued: queued.clone(),
+            },
+            Injector {
+                tx,
+                queued,
+                next_id: 0,
+            },
+        )
+    }
+
+    pub(crate) fn next_event(&mut self) -> Option<Event> {
+        while let Ok(msg) = self.rx.try_recv() {
+            self.received();
+            if let Some(ev) = self.handle_message(msg) {
+                return Some(ev);
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            let msg = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return None;
+                    }
+
+                    self.rx.recv_timeout(deadline - now).ok()?
+                }
+                None => self.rx.recv().ok()?,
+            };
+            self.received();
+
+            if let Some(ev) = self.handle_message(msg) {
+                return Some(ev);
+            }
+        }
+    }
+
+    pub(crate) fn waiter(&self) -> Waiter {
+        Waiter {
+            queued: self.queued.clone(),
+        }
+    }
+
+    fn received(&self) {
+        let mut count = self.queued.count.lock().unwrap();
+        *count = count.saturating_sub(1);
+    }
+
+    fn handle_message(&mut self, msg: Message) -> Option<Event> {
+        match msg {
+            Message::Add(desc) => {
+                self.gamepads.push(Gamepad {
+                    desc,
+                    is_connected: true,
+                });
+
+                Some(Event::new(self.gamepads.len() - 1, EventType::Connected))
+            }
+            Message::Event(ev) if self.accept(&ev) => Some(ev),
+            Message::Event(_) => None,
+        }
+    }
+
+    /// Updates state of gamepad and returns false if event should be dropped.
+    fn accept(&mut self, ev: &Event) -> bool {
+        let gamepad = match self.gamepads.get_mut(ev.id) {
+            Some(gp) => gp,
+            None => {
+                warn!("Dropping event for unknown virtual gamepad: {:?}", ev);
+                return false;
+            }
+        };
+
+        match ev.event {
+            EventType::Connected if gamepad.is_connected => false,
+            EventType::Connected => {
+                gamepad.is_connected = true;
+                true
+            }
+            EventType::Disconnected if !gamepad.is_connected => false,
+            EventType::Disconnected => {
+                gamepad.is_connected = false;
+                true
+            }
+            _ if !gamepad.is_connected => {
+                warn!("Dropping event for disconnected virtual gamepad: {:?}", ev);
+                false
+            }
+            EventType::ButtonPressed(code) | EventType::ButtonReleased(code)
+                if !gamepad.desc.buttons.contains(&code.0) =>
+            {
+                warn!(
+                    "Dropping event for button that gamepad doesn't have: {:?}",
+                    ev
+                );
+                false
+            }
+            EventType::AxisValueChanged(_, code) if !gamepad.desc.axes.contains(&code.0) => {
+                warn!(
+                    "Dropping event for axis that gamepad doesn't have: {:?}",
+                    ev
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub(crate) fn gamepad(&self, id: usize) -> Option<&Gamepad> {
+        self.gamepads.get(id)
+    }
+
+    pub(crate) fn last_gamepad_hint(&self) -> usize {
+        self.gamepads.len()
+    }
+}
+
+/// Waits until there are messages that were not received by `Gilrs`.
+#[derive(Debug)]
+pub(crate) struct Waiter {
+    queued: Arc<Queued>,
+}
+
+impl Waiter {
+    pub(crate) fn wait(&self, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut count = self.queued.count.lock().unwrap();
+
+        while *count == 0 {
+            count = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+
+                    self.queued
+                        .cond
+                        .wait_timeout(count, deadline - now)
+                        .unwrap()
+                        .0
+                }
+                None => self.queued.cond.wait(count).unwrap(),
+            };
+        }
+
+        true
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Gamepad {
+    desc: VirtualGamepad,
+    is_connected: bool,
+}
+
+impl Gamepad {
+    pub(crate) fn name(&self) -> &str {
+        &self.desc.name
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+
+    pub(crate) fn uuid(&self) -> Uuid {
+        self.desc.uuid
+    }
+
+    pub(crate) fn power_info(&self) -> PowerInfo {
+        if self.is_connected {
+            self.desc.power_info
+        } else {
+            PowerInfo::Unknown
+        }
+    }
+
+    pub(crate) fn buttons(&self) -> &[platform::EvCode] {
+        &self.desc.buttons
+    }
+
+    pub(crate) fn axes(&self) -> &[platform::EvCode] {
+        &self.desc.axes
+    }
+
+    pub(crate) fn axis_info(&self, nec: platform::EvCode) -> Option<&AxisInfo> {
+        self.desc
+            .axes
+            .iter()
+            .position(|&c| c == nec)
+            .map(|idx| &self.desc.axes_info[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VirtualGamepad;
+    use crate::native_ev_codes as nec;
+    use crate::{AxisInfo, EventType, Gilrs};
+
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn hotplug_and_filtering() {
+        let (mut gilrs, mut injector) = Gilrs::new_virtual();
+        let info = AxisInfo {
+            min: 0,
+            max: 255,
+            deadzone: None,
+        };
+        let id = injector.add_gamepad(
+            VirtualGamepad::new("Pad")
+                .button(nec::BTN_SOUTH)
+                .axis(nec::AXIS_LSTICKX, info),
+        );
+
+        injector.press(id, nec::BTN_EAST);
+        injector.set_axis(id, nec::AXIS_LSTICKX, 10);
+        injector.disconnect(id);
+        injector.press(id, nec::BTN_SOUTH);
+        injector.connect(id);
+
+        let events = std::iter::from_fn(|| gilrs.next_event())
+            .map(|ev| ev.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                EventType::Connected,
+                EventType::AxisValueChanged(10, nec::AXIS_LSTICKX),
+                EventType::Disconnected,
+                EventType::Connected,
+            ]
+        );
+
+        let gamepad = gilrs.gamepad(id).unwrap();
+        assert_eq!(gamepad.name(), "Pad");
+        assert!(gamepad.is_connected());
+        assert_eq!(gamepad.buttons(), &[nec::BTN_SOUTH]);
+        assert_eq!(gamepad.axis_info(nec::AXIS_LSTICKX).unwrap().max, 255);
+        assert!(gamepad.axis_info(nec::AXIS_RSTICKX).is_none());
+    }
+
+    #[test]
+    fn waiter_after_concurrent_injection() {
+        let (mut gilrs, mut injector) = Gilrs::new_virtual();
+        let id = injector.add_gamepad(VirtualGamepad::new("Pad").button(nec::BTN_SOUTH));
+        let waiter = gilrs.waiter();
+
+        let handle = thread::spawn(move || {
+            for _ in 0..1000 {
+                injector.press(id, nec::BTN_SOUTH);
+            }
+        });
+
+        let mut received = 0;
+        while received < 1001 {
+            if gilrs.next_event().is_some() {
+                received += 1;
+            }
+        }
+        handle.join().unwrap();
+
+        assert!(!waiter.wait(Some(Duration::from_millis(0))));
+    }
+}
@@ -5,7 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay, Ticks};
+use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay};
 use gilrs::Gilrs;
 use std::thread;
 use std::time::Duration;
@@ -18,7 +18,7 @@ fn main() {
         .filter_map(|(id, gp)| if gp.is_ff_supported() { Some(id) } else { None })
         .collect::<Vec<_>>();
 
-    let duration = Ticks::from_ms(150);
+    let duration = gilrs.ff_ticks(Duration::from_millis(150));
     let effect = EffectBuilder::new()
         .add_effect(BaseEffect {
             kind: BaseEffectType::Strong { magnitude: 60_000 },
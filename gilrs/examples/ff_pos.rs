@@ -103,6 +103,14 @@ fn main() {
         .map(|&idx| (idx, [0.0, 0.0, 0.0]))
         .collect::<Vec<_>>();
 
+    // Listeners look along Y axis, so the effect on the left is played mostly by strong motor.
+    for &(idx, _) in &listeners {
+        gilrs
+            .gamepad(idx)
+            .set_listener_orientation([0.0, 1.0, 0.0], [0.0, 0.0, 1.0])
+            .unwrap();
+    }
+
     let mut ref_distance = 10.0;
     let mut rolloff_factor = 0.5;
     let mut max_distance = 100.0;
@@ -28,6 +28,13 @@ pub const BTN_DPAD_DOWN: u16 = 17;
 pub const BTN_DPAD_LEFT: u16 = 18;
 pub const BTN_DPAD_RIGHT: u16 = 19;
 
+pub const BTN_PADDLE1: u16 = 20;
+pub const BTN_PADDLE2: u16 = 21;
+pub const BTN_PADDLE3: u16 = 22;
+pub const BTN_PADDLE4: u16 = 23;
+pub const BTN_MISC1: u16 = 24;
+pub const BTN_TOUCHPAD: u16 = 25;
+
 pub const AXIS_UNKNOWN: u16 = 0;
 
 pub const AXIS_LSTICKX: u16 = 1;
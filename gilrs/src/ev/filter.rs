@@ -126,6 +126,13 @@ fn apply_deadzone(x: f32, y: f32, threshold: f32) -> (f32, f32) {
     }
 }
 
+/// Returns `true` if cached value of `nec` is `val`. Codes without plain mapping are never
+/// unchanged, because their cached value is shared by all mapped elements and mapping layer
+/// already skips elements whose value didn't change.
+fn is_unchanged(gp: &Gamepad<'_>, nec: Code, val: f32) -> bool {
+    gp.has_plain_mapping(nec) && gp.state().value(nec) == val
+}
+
 /// Drops events in dead zone and remaps value to keep it in standard range.
 pub fn deadzone(ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
     match ev {
@@ -146,7 +153,7 @@ pub fn deadzone(ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
                 let other_val = gilrs.gamepad(id).state().value(other_code);
                 let val = apply_deadzone(val, other_val, threshold);
 
-                Some(if gilrs.gamepad(id).state().value(nec) == val.0 {
+                Some(if is_unchanged(&gilrs.gamepad(id), nec, val.0) {
                     Event::new(id, EventType::Dropped)
                 } else {
                     Event {
@@ -158,7 +165,7 @@ pub fn deadzone(ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
             } else {
                 let val = apply_deadzone(val, 0.0, threshold).0;
 
-                Some(if gilrs.gamepad(id).state().value(nec) == val {
+                Some(if is_unchanged(&gilrs.gamepad(id), nec, val) {
                     Event::new(id, EventType::Dropped)
                 } else {
                     Event {
@@ -181,7 +188,7 @@ pub fn deadzone(ev: Option<Event>, gilrs: &mut Gilrs) -> Option<Event> {
             };
             let val = apply_deadzone(val, 0.0, threshold).0;
 
-            Some(if gp.state().value(nec) == val {
+            Some(if is_unchanged(gp, nec, val) {
                 Event::new(id, EventType::Dropped)
             } else {
                 Event {
@@ -45,6 +45,12 @@ impl Code {
     }
 }
 
+impl From<Code> for gilrs_core::EvCode {
+    fn from(code: Code) -> Self {
+        code.0
+    }
+}
+
 /// Holds information about gamepad event.
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -134,6 +140,17 @@ pub enum Button {
     DPadDown = BTN_DPAD_DOWN,
     DPadLeft = BTN_DPAD_LEFT,
     DPadRight = BTN_DPAD_RIGHT,
+    // Back paddles (for example on Xbox Elite controllers)
+    Paddle1 = BTN_PADDLE1,
+    Paddle2 = BTN_PADDLE2,
+    Paddle3 = BTN_PADDLE3,
+    Paddle4 = BTN_PADDLE4,
+    // Other
+    /// Additional button, like share button on Xbox Series controllers or microphone button on
+    /// DualSense.
+    Misc1 = BTN_MISC1,
+    /// Click of touchpad, for example on DualShock 4 and DualSense.
+    Touchpad = BTN_TOUCHPAD,
 
     Unknown = BTN_UNKNOWN,
 }
@@ -265,6 +282,24 @@ impl Axis {
             _ => None,
         }
     }
+
+    /// Returns native event code of axis, if it has one.
+    pub fn to_nec(self) -> Option<Code> {
+        use gilrs_core::native_ev_codes as necs;
+
+        match self {
+            Axis::LeftStickX => Some(necs::AXIS_LSTICKX),
+            Axis::LeftStickY => Some(necs::AXIS_LSTICKY),
+            Axis::LeftZ => Some(necs::AXIS_LEFTZ),
+            Axis::RightStickX => Some(necs::AXIS_RSTICKX),
+            Axis::RightStickY => Some(necs::AXIS_RSTICKY),
+            Axis::RightZ => Some(necs::AXIS_RIGHTZ),
+            Axis::DPadX => Some(necs::AXIS_DPADX),
+            Axis::DPadY => Some(necs::AXIS_DPADY),
+            Axis::Unknown => None,
+        }
+        .map(Code)
+    }
 }
 
 /// Represents `Axis` or `Button`.
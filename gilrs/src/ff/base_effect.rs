@@ -5,26 +5,104 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use std::f32::consts::PI;
 use std::ops::Mul;
 
 use super::time::Ticks;
 
+use gilrs_core::ff::Motor;
+
 /// Kind of [`BaseEffect`](struct.BaseEffect.html).
 ///
-/// Currently base effect support only xinput model of force feedback, which means that  gamepad
-/// have weak and strong motor.
+/// `Weak` and `Strong` follow xinput model of force feedback, where gamepad have weak and strong
+/// motor. Other motors, like ones in triggers, can be used with `Motor` variant.
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[non_exhaustive]
 pub enum BaseEffectType {
-    Weak { magnitude: u16 },
-    Strong { magnitude: u16 },
+    Weak {
+        magnitude: u16,
+    },
+    Strong {
+        magnitude: u16,
+    },
+    /// Magnitude of any motor. Motors that are not supported by gamepad (see
+    /// [`Gamepad::ff_motors()`](../struct.Gamepad.html#method.ff_motors)) are ignored.
+    Motor {
+        motor: Motor,
+        magnitude: u16,
+    },
+    /// Magnitude of `motor` changes periodically. It oscillates between `offset - magnitude` and
+    /// `offset + magnitude` (clamped to `0..=u16::MAX`). `phase` shifts the waveform forward.
+    ///
+    /// Waveform restarts with each repetition of [`Replay`](struct.Replay.html).
+    Periodic {
+        motor: Motor,
+        waveform: Waveform,
+        period: Ticks,
+        phase: Ticks,
+        magnitude: u16,
+        offset: u16,
+    },
+    /// Magnitude of `motor` changes linearly from `start` to `end` over `Replay::play_for`.
+    Ramp {
+        motor: Motor,
+        start: u16,
+        end: u16,
+    },
+    /// Like `Ramp`, but magnitude changes slowly near `start` and `end` and faster between them
+    /// (smoothstep).
+    SmoothRamp {
+        motor: Motor,
+        start: u16,
+        end: u16,
+    },
 }
 
 impl BaseEffectType {
-    fn magnitude(&self) -> u16 {
+    /// Returns effect with magnitude of `motor`. `Strong` and `Weak` motors are returned as
+    /// `Strong` and `Weak` variants.
+    pub(super) fn from_motor(motor: Motor, magnitude: u16) -> Self {
+        match motor {
+            Motor::Strong => BaseEffectType::Strong { magnitude },
+            Motor::Weak => BaseEffectType::Weak { magnitude },
+            motor => BaseEffectType::Motor { motor, magnitude },
+        }
+    }
+
+    /// Returns `Weak`, `Strong` or `Motor` with magnitude at `ticks` from start of playback.
+    fn at(&self, ticks: Ticks, dur: Ticks) -> BaseEffectType {
         match *self {
-            BaseEffectType::Weak { magnitude } => magnitude,
-            BaseEffectType::Strong { magnitude } => magnitude,
+            BaseEffectType::Periodic {
+                motor,
+                waveform,
+                period,
+                phase,
+                magnitude,
+                offset,
+            } => {
+                let pos = if period.0 == 0 {
+                    0.0
+                } else {
+                    ((ticks + phase) % period).0 as f32 / period.0 as f32
+                };
+                let mg = f32::from(offset) + f32::from(magnitude) * waveform.at(pos);
+
+                BaseEffectType::from_motor(motor, clamp_magnitude(mg))
+            }
+            BaseEffectType::Ramp { motor, start, end } => {
+                let pos = ramp_position(ticks, dur);
+                let mg = f32::from(start) + (f32::from(end) - f32::from(start)) * pos;
+
+                BaseEffectType::from_motor(motor, clamp_magnitude(mg))
+            }
+            BaseEffectType::SmoothRamp { motor, start, end } => {
+                let pos = ramp_position(ticks, dur);
+                let pos = pos * pos * (3.0 - 2.0 * pos);
+                let mg = f32::from(start) + (f32::from(end) - f32::from(start)) * pos;
+
+                BaseEffectType::from_motor(motor, clamp_magnitude(mg))
+            }
+            kind => kind,
         }
     }
 }
@@ -33,10 +111,91 @@ impl Mul<f32> for BaseEffectType {
     type Output = BaseEffectType;
 
     fn mul(self, rhs: f32) -> Self::Output {
-        let mg = (self.magnitude() as f32 * rhs) as u16;
+        let mul = |mg: u16| (mg as f32 * rhs) as u16;
         match self {
-            BaseEffectType::Weak { .. } => BaseEffectType::Weak { magnitude: mg },
-            BaseEffectType::Strong { .. } => BaseEffectType::Strong { magnitude: mg },
+            BaseEffectType::Weak { magnitude } => BaseEffectType::Weak {
+                magnitude: mul(magnitude),
+            },
+            BaseEffectType::Strong { magnitude } => BaseEffectType::Strong {
+                magnitude: mul(magnitude),
+            },
+            BaseEffectType::Motor { motor, magnitude } => BaseEffectType::Motor {
+                motor,
+                magnitude: mul(magnitude),
+            },
+            BaseEffectType::Periodic {
+                motor,
+                waveform,
+                period,
+                phase,
+                magnitude,
+                offset,
+            } => BaseEffectType::Periodic {
+                motor,
+                waveform,
+                period,
+                phase,
+                magnitude: mul(magnitude),
+                offset: mul(offset),
+            },
+            BaseEffectType::Ramp { motor, start, end } => BaseEffectType::Ramp {
+                motor,
+                start: mul(start),
+                end: mul(end),
+            },
+            BaseEffectType::SmoothRamp { motor, start, end } => BaseEffectType::SmoothRamp {
+                motor,
+                start: mul(start),
+                end: mul(end),
+            },
+        }
+    }
+}
+
+/// Returns position in range [0.0, 1.0] of `ticks` in ramp, which reaches its end at last tick of
+/// `dur`.
+fn ramp_position(ticks: Ticks, dur: Ticks) -> f32 {
+    if dur.0 <= 1 {
+        1.0
+    } else {
+        (ticks.0 as f32 / (dur.0 - 1) as f32).min(1.0)
+    }
+}
+
+fn clamp_magnitude(mg: f32) -> u16 {
+    if mg <= 0.0 {
+        0
+    } else if mg >= 65_535.0 {
+        0xffff
+    } else {
+        mg as u16
+    }
+}
+
+/// Shape of [`BaseEffectType::Periodic`](enum.BaseEffectType.html#variant.Periodic).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Triangle,
+    /// Rises from -1 to 1, then drops.
+    SawUp,
+    /// Drops from 1 to -1, then rises.
+    SawDown,
+}
+
+impl Waveform {
+    /// Returns value in range [-1.0, 1.0] at `pos` (in range [0.0, 1.0)) of period.
+    fn at(self, pos: f32) -> f32 {
+        match self {
+            Waveform::Sine => (2.0 * PI * pos).sin(),
+            Waveform::Square if pos < 0.5 => 1.0,
+            Waveform::Square => -1.0,
+            Waveform::Triangle if pos < 0.25 => 4.0 * pos,
+            Waveform::Triangle if pos < 0.75 => 2.0 - 4.0 * pos,
+            Waveform::Triangle => 4.0 * pos - 4.0,
+            Waveform::SawUp => 2.0 * pos - 1.0,
+            Waveform::SawDown => 1.0 - 2.0 * pos,
         }
     }
 }
@@ -63,14 +222,14 @@ pub struct BaseEffect {
 }
 
 impl BaseEffect {
-    /// Returns `Weak` or `Strong` after applying envelope.
+    /// Returns `Weak`, `Strong` or `Motor` after applying waveform and envelope.
     pub(super) fn magnitude_at(&self, ticks: Ticks) -> BaseEffectType {
         if let Some(wrapped) = self.scheduling.wrap(ticks) {
             let att =
                 self.scheduling.at(wrapped) * self.envelope.at(wrapped, self.scheduling.play_for);
-            self.kind * att
+            self.kind.at(wrapped, self.scheduling.play_for) * att
         } else {
-            self.kind * 0.0
+            self.kind.at(Ticks(0), self.scheduling.play_for) * 0.0
         }
     }
 }
@@ -149,3 +308,128 @@ impl Default for Replay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weak(magnitude: u16) -> BaseEffectType {
+        BaseEffectType::Weak { magnitude }
+    }
+
+    #[test]
+    fn periodic() {
+        let effect = BaseEffect {
+            kind: BaseEffectType::Periodic {
+                motor: Motor::Weak,
+                waveform: Waveform::Square,
+                period: Ticks(4),
+                phase: Ticks(0),
+                magnitude: 10_000,
+                offset: 20_000,
+            },
+            scheduling: Replay {
+                play_for: Ticks(8),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let magnitudes = (0..8)
+            .map(|t| effect.magnitude_at(Ticks(t)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            magnitudes,
+            [30_000, 30_000, 10_000, 10_000, 30_000, 30_000, 10_000, 10_000]
+                .iter()
+                .map(|&mg| weak(mg))
+                .collect::<Vec<_>>()
+        );
+
+        let triangle = BaseEffectType::Periodic {
+            motor: Motor::Strong,
+            waveform: Waveform::Triangle,
+            period: Ticks(4),
+            phase: Ticks(1),
+            magnitude: 40_000,
+            offset: 0,
+        };
+        assert_eq!(
+            triangle.at(Ticks(0), Ticks(8)),
+            BaseEffectType::Strong { magnitude: 40_000 }
+        );
+        assert_eq!(
+            triangle.at(Ticks(2), Ticks(8)),
+            BaseEffectType::Strong { magnitude: 0 }
+        );
+    }
+
+    #[test]
+    fn waveforms() {
+        let eq = |a: f32, b: f32| (a - b).abs() < 1e-5;
+
+        assert!(eq(Waveform::Sine.at(0.0), 0.0));
+        assert!(eq(Waveform::Sine.at(0.25), 1.0));
+        assert!(eq(Waveform::Sine.at(0.75), -1.0));
+        assert!(eq(Waveform::Triangle.at(0.5), 0.0));
+        assert!(eq(Waveform::Triangle.at(0.75), -1.0));
+        assert!(eq(Waveform::SawUp.at(0.0), -1.0));
+        assert!(eq(Waveform::SawUp.at(0.5), 0.0));
+        assert!(eq(Waveform::SawDown.at(0.0), 1.0));
+    }
+
+    #[test]
+    fn ramp() {
+        let effect = BaseEffect {
+            kind: BaseEffectType::Ramp {
+                motor: Motor::Weak,
+                start: 0,
+                end: 30_000,
+            },
+            scheduling: Replay {
+                play_for: Ticks(4),
+                with_delay: Ticks(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let magnitudes = (0..6)
+            .map(|t| effect.magnitude_at(Ticks(t)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            magnitudes,
+            [0, 10_000, 20_000, 30_000, 0, 0]
+                .iter()
+                .map(|&mg| weak(mg))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn smooth_ramp() {
+        let effect = BaseEffect {
+            kind: BaseEffectType::SmoothRamp {
+                motor: Motor::Weak,
+                start: 0,
+                end: 27_000,
+            },
+            scheduling: Replay {
+                play_for: Ticks(4),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let magnitudes = (0..4)
+            .map(|t| effect.magnitude_at(Ticks(t)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            magnitudes,
+            [0, 7_000, 20_000, 27_000]
+                .iter()
+                .map(|&mg| weak(mg))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::base_effect::{BaseEffect, BaseEffectType, Replay};
+use super::time::{tick_ms, Ticks};
+use super::Motor;
+
+use std::time::Duration;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// How magnitude changes between two keyframes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Interpolation {
+    /// Magnitude stays the same until next keyframe.
+    Step,
+    /// Magnitude changes linearly.
+    #[default]
+    Linear,
+    /// Magnitude changes slowly near keyframes and faster between them (smoothstep).
+    Smooth,
+}
+
+/// Magnitude of motor at given time.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Keyframe {
+    /// Time from the start of the clip in milliseconds.
+    pub time: u32,
+    pub magnitude: u16,
+    /// Interpolation used between this and next keyframe.
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    pub interpolation: Interpolation,
+}
+
+/// Rumble effect described by magnitude curves of strong and weak motor.
+///
+/// Each curve is a list of keyframes. Before first keyframe magnitude is 0, after the last one it
+/// stays at last keyframe's magnitude until the end of clip, which is the time of the last keyframe
+/// in either curve. Keyframes don't have to be sorted.
+///
+/// If `serde-serialize` feature is enabled, clip can be loaded from any format supported by serde,
+/// for example JSON:
+///
+/// ```json
+/// {
+///     "strong": [
+///         { "time": 0, "magnitude": 0 },
+///         { "time": 100, "magnitude": 60000, "interpolation": "Step" },
+///         { "time": 300, "magnitude": 0 }
+///     ],
+///     "weak": [{ "time": 0, "magnitude": 20000 }, { "time": 300, "magnitude": 20000 }]
+/// }
+/// ```
+///
+/// Clip is converted to [`BaseEffect`s](struct.BaseEffect.html) with
+/// [`EffectBuilder::add_clip()`](struct.EffectBuilder.html#method.add_clip). Base effects
+/// repeat with period equal to clip's length, so clip is looped unless
+/// [`Repeat::For`](enum.Repeat.html) is used.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RumbleClip {
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    pub strong: Vec<Keyframe>,
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    pub weak: Vec<Keyframe>,
+}
+
+impl RumbleClip {
+    /// Returns length of the clip in ticks that last `tick_duration`. Use
+    /// [`Gilrs::ff_tick_duration()`](../struct.Gilrs.html#method.ff_tick_duration) to get tick
+    /// duration of force feedback loop.
+    pub fn length(&self, tick_duration: Duration) -> Ticks {
+        self.length_with(tick_ms(tick_duration))
+    }
+
+    /// Converts clip to base effects with ticks that last `tick_duration`. Use
+    /// [`Gilrs::ff_tick_duration()`](../struct.Gilrs.html#method.ff_tick_duration) to get tick
+    /// duration of force feedback loop.
+    pub fn to_base_effects(&self, tick_duration: Duration) -> Vec<BaseEffect> {
+        self.base_effects_with(tick_ms(tick_duration))
+    }
+
+    fn length_with(&self, tick_duration: u32) -> Ticks {
+        self.strong
+            .iter()
+            .chain(self.weak.iter())
+            .map(|kf| Ticks::from_ms_with(kf.time, tick_duration))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Converts clip to base effects with ticks that last `tick_duration` milliseconds.
+    pub(super) fn base_effects_with(&self, tick_duration: u32) -> Vec<BaseEffect> {
+        let length = self.length_with(tick_duration);
+        let mut effects = Vec::new();
+
+        if length == Ticks(0) {
+            return effects;
+        }
+
+        for &(motor, keyframes) in &[(Motor::Strong, &self.strong), (Motor::Weak, &self.weak)] {
+            let mut keyframes = keyframes.clone();
+            keyframes.sort_by_key(|kf| kf.time);
+
+            for (i, kf) in keyframes.iter().enumerate() {
+                let start = Ticks::from_ms_with(kf.time, tick_duration);
+                let (end, next) = match keyframes.get(i + 1) {
+                    Some(next) => (
+                        Ticks::from_ms_with(next.time, tick_duration),
+                        next.magnitude,
+                    ),
+                    None => (length, kf.magnitude),
+                };
+
+                segment(motor, kf, start, end, next, length, &mut effects);
+            }
+        }
+
+        effects
+    }
+}
+
+/// Adds base effects that play from `start` to `end` with magnitude changing from
+/// `kf.magnitude` to `next`.
+fn segment(
+    motor: Motor,
+    kf: &Keyframe,
+    start: Ticks,
+    end: Ticks,
+    next: u16,
+    length: Ticks,
+    effects: &mut Vec<BaseEffect>,
+) {
+    if end <= start {
+        return;
+    }
+
+    let scheduling = |after: Ticks, play_for: Ticks| Replay {
+        after,
+        play_for,
+        with_delay: length - play_for,
+    };
+    let dur = end - start;
+    let from = f32::from(kf.magnitude);
+    let to = f32::from(next);
+
+    match kf.interpolation {
+        Interpolation::Linear | Interpolation::Smooth if kf.magnitude != next => {
+            // Ramps reach their end at last tick of the segment, one tick before `next` is
+            // reached.
+            let x = (dur.0 - 1) as f32 / dur.0 as f32;
+            let kind = if kf.interpolation == Interpolation::Linear {
+                let last = from + (to - from) * x;
+                BaseEffectType::Ramp {
+                    motor,
+                    start: kf.magnitude,
+                    end: last as u16,
+                }
+            } else {
+                let last = from + (to - from) * x * x * (3.0 - 2.0 * x);
+                BaseEffectType::SmoothRamp {
+                    motor,
+                    start: kf.magnitude,
+                    end: last as u16,
+                }
+            };
+            effects.push(BaseEffect {
+                kind,
+                scheduling: scheduling(start, dur),
+                envelope: Default::default(),
+            });
+        }
+        _ => effects.push(constant(motor, kf.magnitude, scheduling(start, dur))),
+    }
+}
+
+fn constant(motor: Motor, magnitude: u16, scheduling: Replay) -> BaseEffect {
+    BaseEffect {
+        kind: BaseEffectType::from_motor(motor, magnitude),
+        scheduling,
+        envelope: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kf(time: u32, magnitude: u16, interpolation: Interpolation) -> Keyframe {
+        Keyframe {
+            time,
+            magnitude,
+            interpolation,
+        }
+    }
+
+    /// Returns (strong, weak) magnitudes of all effects at `ticks`.
+    fn magnitude_at(effects: &[BaseEffect], ticks: Ticks) -> (u16, u16) {
+        effects.iter().fold((0, 0), |(strong, weak), effect| {
+            match effect.magnitude_at(ticks) {
+                BaseEffectType::Strong { magnitude } => (strong + magnitude, weak),
+                BaseEffectType::Weak { magnitude } => (strong, weak + magnitude),
+                _ => unreachable!(),
+            }
+        })
+    }
+
+    #[test]
+    fn keyframes_to_base_effects() {
+        let clip = RumbleClip {
+            strong: vec![
+                kf(200, 0, Interpolation::Linear),
+                kf(0, 30_000, Interpolation::Step),
+                kf(100, 40_000, Interpolation::Linear),
+            ],
+            weak: vec![
+                kf(50, 10_000, Interpolation::Smooth),
+                kf(150, 0, Interpolation::Step),
+            ],
+        };
+        let tick = Duration::from_millis(50);
+        assert_eq!(clip.length(tick), Ticks(4));
+        assert_eq!(clip.length(Duration::from_millis(10)), Ticks(20));
+
+        let effects = clip.to_base_effects(tick);
+        assert_eq!(effects.len(), 4);
+        let magnitudes = (0..9)
+            .map(|t| magnitude_at(&effects, Ticks(t)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            magnitudes,
+            [
+                (30_000, 0),
+                (30_000, 10_000),
+                (40_000, 5_000),
+                (20_000, 0),
+                (30_000, 0),
+                (30_000, 10_000),
+                (40_000, 5_000),
+                (20_000, 0),
+                (30_000, 0),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn deserialize() {
+        let clip: RumbleClip = serde_json::from_str(
+            r#"{ "strong": [{ "time": 0, "magnitude": 100, "interpolation": "Step" }] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(clip.strong, [kf(0, 100, Interpolation::Step)]);
+        assert!(clip.weak.is_empty());
+    }
+}
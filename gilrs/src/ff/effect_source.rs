@@ -11,6 +11,7 @@ use std::{fmt, u16};
 
 use super::base_effect::{BaseEffect, BaseEffectType};
 use super::time::{Repeat, Ticks};
+use super::Motor;
 
 use vec_map::VecMap;
 
@@ -252,6 +253,8 @@ pub(crate) struct EffectSource {
     pub(super) distance_model: DistanceModel,
     pub(super) position: [f32; 3],
     pub(super) gain: f32,
+    pub(super) priority: u8,
+    pub(super) ducking: f32,
     pub(super) state: EffectState,
 }
 
@@ -271,11 +274,25 @@ impl EffectSource {
             distance_model: dist_model,
             position,
             gain,
+            priority: 0,
+            ducking: 1.0,
             state: EffectState::Stopped,
         }
     }
 
-    pub(super) fn combine_base_effects(&mut self, ticks: Ticks, actor_pos: [f32; 3]) -> Magnitude {
+    /// Stops effect if it played for longer than allowed by `repeat`.
+    pub(super) fn update_state(&mut self, ticks: Ticks) {
+        if let EffectState::Playing { since } = self.state {
+            match self.repeat {
+                Repeat::For(max_dur) if ticks - since > max_dur => {
+                    self.state = EffectState::Stopped;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub(super) fn combine_base_effects(&mut self, ticks: Ticks, listener: &Listener) -> Magnitude {
         let ticks = match self.state {
             EffectState::Playing { since } => {
                 debug_assert!(ticks >= since);
@@ -284,16 +301,9 @@ impl EffectSource {
             EffectState::Stopped => return Magnitude::zero(),
         };
 
-        match self.repeat {
-            Repeat::For(max_dur) if ticks > max_dur => {
-                self.state = EffectState::Stopped;
-            }
-            _ => (),
-        }
-
         let attenuation = self
             .distance_model
-            .attenuation(self.position.distance(actor_pos))
+            .attenuation(self.position.distance(listener.position))
             * self.gain;
         if attenuation < 0.05 {
             return Magnitude::zero();
@@ -303,55 +313,141 @@ impl EffectSource {
         for effect in &self.base_effects {
             match effect.magnitude_at(ticks) {
                 BaseEffectType::Strong { magnitude } => {
-                    final_magnitude.strong = final_magnitude.strong.saturating_add(magnitude)
+                    final_magnitude.add(Motor::Strong, magnitude)
                 }
-                BaseEffectType::Weak { magnitude } => {
-                    final_magnitude.weak = final_magnitude.weak.saturating_add(magnitude)
+                BaseEffectType::Weak { magnitude } => final_magnitude.add(Motor::Weak, magnitude),
+                BaseEffectType::Motor { motor, magnitude } => final_magnitude.add(motor, magnitude),
+                // `magnitude_at()` resolves these types to `Weak`, `Strong` or `Motor`.
+                kind @ BaseEffectType::Periodic { .. }
+                | kind @ BaseEffectType::Ramp { .. }
+                | kind @ BaseEffectType::SmoothRamp { .. } => {
+                    warn!("Skipping unresolved base effect {:?}", kind)
                 }
             };
         }
-        final_magnitude * attenuation
+        final_magnitude = final_magnitude * attenuation;
+
+        match listener.right {
+            Some(right) if self.position != listener.position => {
+                let direction = self.position.sub(listener.position).normalize();
+                final_magnitude.pan(direction.dot(right))
+            }
+            _ => final_magnitude,
+        }
     }
 }
 
-/// (strong, weak) pair.
-#[derive(Copy, Clone, Debug)]
+/// Position and orientation of gamepad.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(super) struct Listener {
+    pub position: [f32; 3],
+    /// Unit vector pointing to the right of the listener. If `None`, effects are not panned.
+    pub right: Option<[f32; 3]>,
+}
+
+impl Listener {
+    /// Changes orientation of listener. Like in OpenAL, `forward` and `up` don't have to be
+    /// normalized, but they should be perpendicular.
+    pub fn set_orientation(&mut self, forward: [f32; 3], up: [f32; 3]) {
+        let right = forward.cross(up);
+        self.right = if right.dot(right) > 0.0 {
+            Some(right.normalize())
+        } else {
+            None
+        };
+    }
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Listener {
+            position: [0.0, 0.0, 0.0],
+            right: None,
+        }
+    }
+}
+
+/// Magnitudes of motors.
+#[derive(Clone, Debug, Default)]
 pub(super) struct Magnitude {
-    pub strong: u16,
-    pub weak: u16,
+    motors: Vec<(Motor, u16)>,
 }
 
 impl Magnitude {
     pub fn zero() -> Self {
-        Magnitude { strong: 0, weak: 0 }
+        Magnitude { motors: Vec::new() }
+    }
+
+    pub fn get(&self, motor: Motor) -> u16 {
+        self.motors
+            .iter()
+            .find(|&&(m, _)| m == motor)
+            .map_or(0, |&(_, magnitude)| magnitude)
+    }
+
+    pub fn add(&mut self, motor: Motor, magnitude: u16) {
+        match self.motors.iter_mut().find(|&&mut (m, _)| m == motor) {
+            Some(&mut (_, ref mut mg)) => *mg = mg.saturating_add(magnitude),
+            None => self.motors.push((motor, magnitude)),
+        }
+    }
+
+    pub fn motors(&self) -> &[(Motor, u16)] {
+        &self.motors
+    }
+
+    /// Moves magnitude to left motors (strong and left trigger) if `pan` is negative or to right
+    /// motors (weak and right trigger) if it's positive. `pan` is in range \[-1.0, 1.0\].
+    pub fn pan(self, pan: f32) -> Self {
+        let mut panned = Magnitude::zero();
+        let pairs = [
+            (Motor::Strong, Motor::Weak),
+            (Motor::LeftTrigger, Motor::RightTrigger),
+        ];
+
+        for &(left, right) in &pairs {
+            let (l, r) = (f32::from(self.get(left)), f32::from(self.get(right)));
+            let to_left = r * (-pan).max(0.0);
+            let to_right = l * pan.max(0.0);
+
+            panned.add(left, (l - to_right + to_left).min(65_535.0) as u16);
+            panned.add(right, (r - to_left + to_right).min(65_535.0) as u16);
+        }
+
+        // Motors without pair are not panned.
+        for &(motor, magnitude) in &self.motors {
+            if !pairs.iter().any(|&(l, r)| motor == l || motor == r) {
+                panned.add(motor, magnitude);
+            }
+        }
+
+        panned
     }
 }
 
 impl Mul<f32> for Magnitude {
     type Output = Magnitude;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(mut self, rhs: f32) -> Self::Output {
         debug_assert!(rhs >= 0.0);
-        let strong = self.strong as f32 * rhs;
-        let strong = if strong > u16::MAX as f32 {
-            u16::MAX
-        } else {
-            strong as u16
-        };
-        let weak = self.weak as f32 * rhs;
-        let weak = if weak > u16::MAX as f32 {
-            u16::MAX
-        } else {
-            weak as u16
-        };
-        Magnitude { strong, weak }
+        for &mut (_, ref mut magnitude) in &mut self.motors {
+            let mg = *magnitude as f32 * rhs;
+            *magnitude = if mg > u16::MAX as f32 {
+                u16::MAX
+            } else {
+                mg as u16
+            };
+        }
+
+        self
     }
 }
 
 impl AddAssign for Magnitude {
     fn add_assign(&mut self, rhs: Magnitude) {
-        self.strong = self.strong.saturating_add(rhs.strong);
-        self.weak = self.weak.saturating_add(rhs.weak);
+        for (motor, magnitude) in rhs.motors {
+            self.add(motor, magnitude);
+        }
     }
 }
 
@@ -359,6 +455,10 @@ trait SliceVecExt {
     type Base;
 
     fn distance(self, from: Self) -> Self::Base;
+    fn sub(self, rhs: Self) -> Self;
+    fn dot(self, rhs: Self) -> Self::Base;
+    fn cross(self, rhs: Self) -> Self;
+    fn normalize(self) -> Self;
 }
 
 impl SliceVecExt for [f32; 3] {
@@ -368,4 +468,83 @@ impl SliceVecExt for [f32; 3] {
         ((from[0] - self[0]).powi(2) + (from[1] - self[1]).powi(2) + (from[2] - self[2]).powi(2))
             .sqrt()
     }
+
+    fn sub(self, rhs: Self) -> Self {
+        [self[0] - rhs[0], self[1] - rhs[1], self[2] - rhs[2]]
+    }
+
+    fn dot(self, rhs: Self) -> f32 {
+        self[0] * rhs[0] + self[1] * rhs[1] + self[2] * rhs[2]
+    }
+
+    fn cross(self, rhs: Self) -> Self {
+        [
+            self[1] * rhs[2] - self[2] * rhs[1],
+            self[2] * rhs[0] - self[0] * rhs[2],
+            self[0] * rhs[1] - self[1] * rhs[0],
+        ]
+    }
+
+    fn normalize(self) -> Self {
+        let len = self.dot(self).sqrt();
+        [self[0] / len, self[1] / len, self[2] / len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panning() {
+        let mut magnitude = Magnitude::zero();
+        magnitude.add(Motor::Strong, 1000);
+        magnitude.add(Motor::Weak, 3000);
+        let pan = |pan| {
+            let m = magnitude.clone().pan(pan);
+            (m.get(Motor::Strong), m.get(Motor::Weak))
+        };
+
+        assert_eq!(pan(0.0), (1000, 3000));
+        assert_eq!(pan(-1.0), (4000, 0));
+        assert_eq!(pan(-0.5), (2500, 1500));
+        assert_eq!(pan(0.5), (500, 3500));
+        assert_eq!(pan(1.0), (0, 4000));
+
+        magnitude.add(Motor::LeftTrigger, 500);
+        let m = magnitude.pan(1.0);
+        assert_eq!(m.get(Motor::Strong), 0);
+        assert_eq!(m.get(Motor::LeftTrigger), 0);
+        assert_eq!(m.get(Motor::RightTrigger), 500);
+    }
+
+    #[test]
+    fn listener_orientation() {
+        let mut effect = EffectSource::new(
+            vec![BaseEffect {
+                kind: BaseEffectType::Strong { magnitude: 2000 },
+                ..Default::default()
+            }],
+            VecMap::new(),
+            Repeat::Infinitely,
+            DistanceModel::None,
+            [10.0, 0.0, 0.0],
+            1.0,
+        );
+        effect.state = EffectState::Playing { since: Ticks(0) };
+
+        let mut listener = Listener::default();
+        let m = effect.combine_base_effects(Ticks(0), &listener);
+        assert_eq!((m.get(Motor::Strong), m.get(Motor::Weak)), (2000, 0));
+
+        // Effect is on the right.
+        listener.set_orientation([0.0, 0.0, -1.0], [0.0, 1.0, 0.0]);
+        let m = effect.combine_base_effects(Ticks(0), &listener);
+        assert_eq!((m.get(Motor::Strong), m.get(Motor::Weak)), (0, 2000));
+
+        // Effect is in front of listener.
+        listener.set_orientation([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+        let m = effect.combine_base_effects(Ticks(0), &listener);
+        assert_eq!((m.get(Motor::Strong), m.get(Motor::Weak)), (2000, 0));
+    }
 }
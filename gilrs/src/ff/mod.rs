@@ -24,7 +24,8 @@
 //!
 //! ```rust
 //! use gilrs::Gilrs;
-//! use gilrs::ff::{EffectBuilder, Replay, BaseEffect, BaseEffectType, Ticks};
+//! use gilrs::ff::{EffectBuilder, Replay, BaseEffect, BaseEffectType};
+//! use std::time::Duration;
 //!
 //! let mut gilrs = Gilrs::new().unwrap();
 //! let support_ff = gilrs
@@ -32,7 +33,7 @@
 //!     .filter_map(|(id, gp)| if gp.is_ff_supported() { Some(id) } else { None })
 //!     .collect::<Vec<_>>();
 //!
-//! let duration = Ticks::from_ms(150);
+//! let duration = gilrs.ff_ticks(Duration::from_millis(150));
 //! let effect = EffectBuilder::new()
 //!     .add_effect(BaseEffect {
 //!         kind: BaseEffectType::Strong { magnitude: 60_000 },
@@ -53,23 +54,26 @@
 //! See [`examples/ff_pos.rs`](https://gitlab.com/gilrs-project/gilrs/blob/v0.8.0/examples/ff_pos.rs) for
 //! more advanced example.
 mod base_effect;
+mod clip;
 mod effect_source;
 pub(crate) mod server;
 mod time;
 
-pub use self::base_effect::{BaseEffect, BaseEffectType, Envelope, Replay};
+pub use self::base_effect::{BaseEffect, BaseEffectType, Envelope, Replay, Waveform};
+pub use self::clip::{Interpolation, Keyframe, RumbleClip};
 pub use self::effect_source::{DistanceModel, DistanceModelError};
-#[allow(unused_imports)]
 pub(crate) use self::time::TICK_DURATION;
 pub use self::time::{Repeat, Ticks};
+pub use gilrs_core::ff::{EffectType, Motor};
 
 use std::error::Error as StdError;
 use std::hash::{Hash, Hasher};
 use std::sync::mpsc::{SendError, Sender};
+use std::sync::Arc;
 use std::{f32, fmt};
 
 use self::effect_source::EffectSource;
-use crate::ff::server::Message;
+use crate::ff::server::{Message, PlaybackStatus};
 use crate::gamepad::{Gamepad, GamepadId, Gilrs};
 use crate::utils;
 
@@ -85,6 +89,7 @@ use vec_map::VecMap;
 pub struct Effect {
     id: usize,
     tx: Sender<Message>,
+    playing: Arc<PlaybackStatus>,
 }
 
 impl PartialEq for Effect {
@@ -107,6 +112,7 @@ impl Clone for Effect {
         Effect {
             id: self.id,
             tx: self.tx.clone(),
+            playing: self.playing.clone(),
         }
     }
 }
@@ -120,17 +126,27 @@ impl Drop for Effect {
 impl Effect {
     /// Plays effect on all associated gamepads.
     pub fn play(&self) -> Result<(), Error> {
+        self.playing.set_playing(true);
         self.tx.send(Message::Play { id: self.id })?;
 
         Ok(())
     }
 
     pub fn stop(&self) -> Result<(), Error> {
+        self.playing.set_playing(false);
         self.tx.send(Message::Stop { id: self.id })?;
 
         Ok(())
     }
 
+    /// Returns true if effect is playing. Effect stops when `stop()` is called or, if it was
+    /// created with `Repeat::For`, when its duration elapses.
+    ///
+    /// Effect is considered playing even if it's not associated with any gamepad.
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_playing()
+    }
+
     /// Changes gamepads that are associated with effect. Effect will be only played on gamepads
     /// from last call to this function.
     ///
@@ -224,31 +240,60 @@ impl Effect {
 
         Ok(())
     }
+
+    /// Changes priority of the effect. See
+    /// [`EffectBuilder::priority()`](struct.EffectBuilder.html#method.priority) for details.
+    pub fn set_priority(&self, priority: u8) -> Result<(), Error> {
+        self.tx.send(Message::SetPriority {
+            id: self.id,
+            priority,
+        })?;
+
+        Ok(())
+    }
+
+    /// Changes ducking of the effect. `ducking` will be clamped to \[0.0, 1.0\]. See
+    /// [`EffectBuilder::ducking()`](struct.EffectBuilder.html#method.ducking) for details.
+    pub fn set_ducking(&self, ducking: f32) -> Result<(), Error> {
+        let ducking = utils::clamp(ducking, 0.0, 1.0);
+        self.tx.send(Message::SetDucking {
+            id: self.id,
+            ducking,
+        })?;
+
+        Ok(())
+    }
 }
 
 /// Creates new [`Effect`](struct.Effect.html).
 #[derive(Clone, PartialEq, Debug)]
 pub struct EffectBuilder {
     base_effects: Vec<BaseEffect>,
+    clips: Vec<RumbleClip>,
     devices: VecMap<()>,
     repeat: Repeat,
     dist_model: DistanceModel,
     position: [f32; 3],
     gain: f32,
+    priority: u8,
+    ducking: f32,
 }
 
 impl EffectBuilder {
     /// Creates new builder with following defaults: no gamepads, no base effects, repeat set to
-    /// infinitely, no distance model, position in (0.0, 0.0, 0.0) and gain 1.0. Use `finish()` to
-    /// create new effect.
+    /// infinitely, no distance model, position in (0.0, 0.0, 0.0), gain 1.0, priority 0 and no
+    /// ducking. Use `finish()` to create new effect.
     pub fn new() -> Self {
         EffectBuilder {
             base_effects: Vec::new(),
+            clips: Vec::new(),
             devices: VecMap::new(),
             repeat: Repeat::Infinitely,
             dist_model: DistanceModel::None,
             position: [0.0, 0.0, 0.0],
             gain: 1.0,
+            priority: 0,
+            ducking: 1.0,
         }
     }
 
@@ -258,6 +303,13 @@ impl EffectBuilder {
         self
     }
 
+    /// Adds base effects created from [`RumbleClip`](struct.RumbleClip.html). Clip is converted
+    /// to base effects in `finish()`, using tick duration of `Gilrs`.
+    pub fn add_clip(&mut self, clip: &RumbleClip) -> &mut Self {
+        self.clips.push(clip.clone());
+        self
+    }
+
     /// Changes gamepads that are associated with effect. Effect will be only played on gamepads
     /// from last call to this function.
     pub fn gamepads(&mut self, ids: &[GamepadId]) -> &mut Self {
@@ -298,6 +350,25 @@ impl EffectBuilder {
         self
     }
 
+    /// Changes priority of the effect. When more effects are playing on a gamepad than its voice
+    /// limit (see [`GilrsBuilder::set_ff_voice_limit()`][limit]), only effects with the highest
+    /// priority are played. Newer effects take precedence over older ones with the same priority.
+    ///
+    /// [limit]: ../struct.GilrsBuilder.html#method.set_ff_voice_limit
+    pub fn priority(&mut self, priority: u8) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Changes ducking of the effect. While this effect is playing, magnitude of effects with
+    /// lower priority on the same gamepad is multiplied by `ducking`. If more effects duck the
+    /// same effect, the lowest value is used. `ducking` will be clamped to \[0.0, 1.0\], 1.0
+    /// (default) disables ducking.
+    pub fn ducking(&mut self, ducking: f32) -> &mut Self {
+        self.ducking = utils::clamp(ducking, 0.0, 1.0);
+        self
+    }
+
     /// Validates all parameters and creates new effect.
     ///
     /// # Errors
@@ -319,23 +390,86 @@ impl EffectBuilder {
             }
         }
 
+        let id = gilrs.next_ff_id();
+        let tick_duration = gilrs.ff_tick_ms();
+        self.create(id, gilrs.ff_sender(), tick_duration)
+    }
+
+    /// Validates distance model and sends new effect with `id` to the server. Clips are converted
+    /// with ticks that last `tick_duration` milliseconds.
+    fn create(&self, id: usize, tx: &Sender<Message>, tick_duration: u32) -> Result<Effect, Error> {
         self.dist_model.validate()?;
 
-        let effect = EffectSource::new(
-            self.base_effects.clone(),
+        let mut base_effects = self.base_effects.clone();
+        for clip in &self.clips {
+            base_effects.extend(clip.base_effects_with(tick_duration));
+        }
+        let mut effect = EffectSource::new(
+            base_effects,
             self.devices.clone(),
             self.repeat,
             self.dist_model,
             self.position,
             self.gain,
         );
-        let id = gilrs.next_ff_id();
-        let tx = gilrs.ff_sender();
+        effect.priority = self.priority;
+        effect.ducking = self.ducking;
+        let playing = Arc::new(PlaybackStatus::default());
         tx.send(Message::Create {
             id,
             effect: Box::new(effect),
+            playing: playing.clone(),
         })?;
-        Ok(Effect { id, tx: tx.clone() })
+        Ok(Effect {
+            id,
+            tx: tx.clone(),
+            playing,
+        })
+    }
+}
+
+/// Force feedback features supported by gamepad. Returned by
+/// [`Gamepad::ff_capabilities()`](../struct.Gamepad.html#method.ff_capabilities).
+///
+/// Currently capabilities are only reported on Linux, on other platforms they are empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Types of effects supported by gamepad's driver.
+    pub effects: Vec<EffectType>,
+    /// Waveforms supported by periodic effects of gamepad's driver.
+    pub waveforms: Vec<Waveform>,
+    /// Maximum number of effects that can be uploaded to gamepad at the same time, if known.
+    pub max_effects: Option<u32>,
+    /// True if gamepad supports changing gain.
+    pub gain: bool,
+    /// True if gamepad supports changing strength of autocenter.
+    pub autocenter: bool,
+}
+
+impl Capabilities {
+    pub(crate) fn from_native(caps: gilrs_core::ff::Capabilities) -> Self {
+        use gilrs_core::ff::Waveform as NativeWaveform;
+
+        let waveforms = caps
+            .waveforms
+            .iter()
+            .map(|waveform| match waveform {
+                NativeWaveform::Square => Waveform::Square,
+                NativeWaveform::Triangle => Waveform::Triangle,
+                NativeWaveform::Sine => Waveform::Sine,
+                NativeWaveform::SawUp => Waveform::SawUp,
+                NativeWaveform::SawDown => Waveform::SawDown,
+            })
+            .collect();
+
+        Capabilities {
+            effects: caps.effects,
+            waveforms,
+            max_effects: caps.max_effects,
+            gain: caps.gain,
+            autocenter: caps.autocenter,
+        }
     }
 }
 
@@ -449,4 +583,117 @@ mod tests {
         assert_eq!(replay.at(Ticks(60)), 0.0);
         assert_eq!(replay.at(Ticks(70)), 0.0);
     }
+
+    /// Returns effect created on server that is ticked manually with `tick()`.
+    fn effect_on_server(repeat: Repeat) -> (Effect, server::Server) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect::default())
+            .repeat(repeat)
+            .create(0, &tx, TICK_DURATION)
+            .unwrap();
+
+        let tick_duration = std::time::Duration::from_millis(TICK_DURATION.into());
+        (effect, server::Server::new(rx, None, tick_duration))
+    }
+
+    fn tick(server: &mut server::Server) {
+        server.handle_messages();
+        server.play();
+        server.advance(Ticks(1));
+    }
+
+    #[test]
+    fn is_playing() {
+        let (effect, mut server) = effect_on_server(Repeat::For(Ticks(2)));
+        tick(&mut server);
+        assert!(!effect.is_playing());
+
+        effect.play().unwrap();
+        assert!(effect.is_playing());
+        // Tick that happens before `Play` is handled must not overwrite status.
+        server.play();
+        assert!(effect.is_playing());
+
+        for _ in 0..3 {
+            tick(&mut server);
+            assert!(effect.is_playing());
+        }
+        tick(&mut server);
+        assert!(!effect.is_playing());
+
+        effect.play().unwrap();
+        tick(&mut server);
+        effect.stop().unwrap();
+        assert!(!effect.is_playing());
+        server.play();
+        assert!(!effect.is_playing());
+        tick(&mut server);
+        assert!(!effect.is_playing());
+    }
+
+    #[test]
+    fn pause() {
+        let (effect, mut server) = effect_on_server(Repeat::For(Ticks(2)));
+
+        effect.tx.send(Message::SetPaused { paused: true }).unwrap();
+        effect.play().unwrap();
+        for _ in 0..10 {
+            tick(&mut server);
+        }
+        assert!(effect.is_playing());
+
+        effect
+            .tx
+            .send(Message::SetPaused { paused: false })
+            .unwrap();
+        for _ in 0..3 {
+            tick(&mut server);
+            assert!(effect.is_playing());
+        }
+        tick(&mut server);
+        assert!(!effect.is_playing());
+    }
+
+    #[test]
+    fn tick_duration() {
+        use crate::{Error as GilrsError, GilrsBuilder};
+        use std::time::Duration;
+
+        let err = GilrsBuilder::new()
+            .set_ff_tick_duration(Duration::from_micros(500))
+            .build_virtual()
+            .err()
+            .unwrap();
+        match err {
+            GilrsError::InvalidFfTickDuration => (),
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let (gilrs, _injector) = GilrsBuilder::new()
+            .set_ff_tick_duration(Duration::from_millis(10))
+            .build_virtual()
+            .unwrap();
+        assert_eq!(gilrs.ff_tick_duration(), Duration::from_millis(10));
+        assert_eq!(gilrs.ff_ticks(Duration::from_millis(25)), Ticks(3));
+
+        let clip = RumbleClip {
+            strong: vec![
+                Keyframe {
+                    time: 0,
+                    magnitude: 1000,
+                    interpolation: Interpolation::Step,
+                },
+                Keyframe {
+                    time: 100,
+                    magnitude: 0,
+                    interpolation: Interpolation::Step,
+                },
+            ],
+            weak: Vec::new(),
+        };
+        assert_eq!(clip.length(gilrs.ff_tick_duration()), Ticks(10));
+        let effects = clip.to_base_effects(gilrs.ff_tick_duration());
+        assert_eq!(effects[0].scheduling.play_for, Ticks(10));
+    }
 }
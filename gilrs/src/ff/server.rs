@@ -5,15 +5,18 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::effect_source::{DistanceModel, EffectSource, EffectState, Magnitude};
-use super::time::{Repeat, Ticks, TICK_DURATION};
+use super::effect_source::{DistanceModel, EffectSource, EffectState, Listener, Magnitude};
+use super::time::{Repeat, Ticks};
 
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::gamepad::GamepadId;
+use crate::utils;
 use gilrs_core::FfDevice;
 
 use vec_map::VecMap;
@@ -23,6 +26,7 @@ pub(crate) enum Message {
     Create {
         id: usize,
         effect: Box<EffectSource>,
+        playing: Arc<PlaybackStatus>,
     },
     HandleCloned {
         id: usize,
@@ -47,6 +51,32 @@ pub(crate) enum Message {
         id: usize,
         position: [f32; 3],
     },
+    SetListenerOrientation {
+        id: usize,
+        forward: [f32; 3],
+        up: [f32; 3],
+    },
+    SetDeviceGain {
+        id: usize,
+        gain: f32,
+    },
+    SetHardwareGain {
+        id: usize,
+        gain: u16,
+    },
+    SetAutocenter {
+        id: usize,
+        strength: u16,
+    },
+    SetMasterGain {
+        gain: f32,
+    },
+    SetMuted {
+        muted: bool,
+    },
+    SetPaused {
+        paused: bool,
+    },
     SetGamepads {
         id: usize,
         gamepads: VecMap<()>,
@@ -71,6 +101,14 @@ pub(crate) enum Message {
         id: usize,
         gain: f32,
     },
+    SetPriority {
+        id: usize,
+        priority: u8,
+    },
+    SetDucking {
+        id: usize,
+        ducking: f32,
+    },
 }
 
 impl Message {
@@ -79,16 +117,74 @@ impl Message {
         use self::Message::*;
 
         match self {
-            &SetListenerPosition { .. } | &HandleCloned { .. } | &HandleDropped { .. } => true,
+            &SetListenerPosition { .. }
+            | &SetListenerOrientation { .. }
+            | &HandleCloned { .. }
+            | &HandleDropped { .. } => true,
             _ => false,
         }
     }
 }
 
+/// Playback status of effect shared by its handles and the server.
+///
+/// Handles change status in `play()` and `stop()`, before the server handles their message, so
+/// the server must not overwrite it with state computed before that. Every change made by handles
+/// increments version stored next to the status and the server only updates status when it has
+/// handled the same number of `Play` and `Stop` messages.
+#[derive(Debug, Default)]
+pub(crate) struct PlaybackStatus(AtomicUsize);
+
+impl PlaybackStatus {
+    const PLAYING: usize = 1;
+    const VERSION_MASK: usize = usize::MAX >> 1;
+
+    pub(crate) fn is_playing(&self) -> bool {
+        self.0.load(Ordering::SeqCst) & Self::PLAYING != 0
+    }
+
+    /// Changes status and increments its version. Has to be called before sending `Play` or
+    /// `Stop` message.
+    pub(crate) fn set_playing(&self, playing: bool) {
+        let mut current = self.0.load(Ordering::SeqCst);
+        loop {
+            let version = ((current >> 1) + 1) & Self::VERSION_MASK;
+            let new = version << 1 | playing as usize;
+            match self
+                .0
+                .compare_exchange_weak(current, new, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Changes status if `handled` (number of `Play` and `Stop` messages handled by the server)
+    /// matches current version.
+    fn update(&self, handled: usize, playing: bool) {
+        let current = self.0.load(Ordering::SeqCst);
+        if current >> 1 == handled & Self::VERSION_MASK {
+            let new = current & !Self::PLAYING | playing as usize;
+            // Fails only if handle changed status in the meantime, which is what we want.
+            let _ = self
+                .0
+                .compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Gain applied to all devices.
+#[derive(Copy, Clone, Debug)]
+struct Master {
+    gain: f32,
+    muted: bool,
+}
+
 #[derive(Debug)]
 struct Device {
     inner: FfDevice,
-    position: [f32; 3],
+    listener: Listener,
     gain: f32,
 }
 
@@ -96,6 +192,10 @@ struct Effect {
     source: EffectSource,
     /// Number of created effect's handles.
     count: usize,
+    /// Shared with handles.
+    playing: Arc<PlaybackStatus>,
+    /// Number of handled `Play` and `Stop` messages.
+    handled: usize,
 }
 
 impl Effect {
@@ -108,11 +208,12 @@ impl Effect {
         self.count -= 1;
         self.count
     }
-}
 
-impl From<EffectSource> for Effect {
-    fn from(source: EffectSource) -> Self {
-        Effect { source, count: 1 }
+    /// Stops effect if it played for requested time and updates playback status.
+    fn update_state(&mut self, tick: Ticks) {
+        self.source.update_state(tick);
+        let playing = self.source.state != EffectState::Stopped;
+        self.playing.update(self.handled, playing);
     }
 }
 
@@ -134,21 +235,47 @@ impl From<FfDevice> for Device {
     fn from(inner: FfDevice) -> Self {
         Device {
             inner,
-            position: [0.0, 0.0, 0.0],
+            listener: Listener::default(),
             gain: 1.0,
         }
     }
 }
 
-pub(crate) fn run(rx: Receiver<Message>) {
-    let mut effects = VecMap::<Effect>::new();
-    let mut devices = VecMap::<Device>::new();
-    let sleep_dur = Duration::from_millis(TICK_DURATION.into());
-    let mut tick = Ticks(0);
+pub(super) struct Server {
+    rx: Receiver<Message>,
+    effects: VecMap<Effect>,
+    devices: VecMap<Device>,
+    master: Master,
+    paused: bool,
+    tick: Ticks,
+    tick_duration: Duration,
+    voice_limit: Option<usize>,
+}
 
-    loop {
-        let t1 = Instant::now();
-        while let Ok(ev) = rx.try_recv() {
+impl Server {
+    pub(super) fn new(
+        rx: Receiver<Message>,
+        voice_limit: Option<usize>,
+        tick_duration: Duration,
+    ) -> Self {
+        Server {
+            rx,
+            effects: VecMap::new(),
+            devices: VecMap::new(),
+            master: Master {
+                gain: 1.0,
+                muted: false,
+            },
+            paused: false,
+            tick: Ticks(0),
+            tick_duration,
+            voice_limit,
+        }
+    }
+
+    /// Handles all messages that are waiting in the channel.
+    pub(super) fn handle_messages(&mut self) {
+        while let Ok(ev) = self.rx.try_recv() {
             if ev.use_trace_level() {
                 trace!("New ff event: {:?}", ev);
             } else {
@@ -156,38 +283,93 @@ pub(crate) fn run(rx: Receiver<Message>) {
             }
 
             match ev {
-                Message::Create { id, effect } => {
-                    effects.insert(id, (*effect).into());
+                Message::Create {
+                    id,
+                    effect,
+                    playing,
+                } => {
+                    self.effects.insert(
+                        id,
+                        Effect {
+                            source: *effect,
+                            count: 1,
+                            playing,
+                            handled: 0,
+                        },
+                    );
                 }
                 Message::Play { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
-                        effect.source.state = EffectState::Playing { since: tick }
+                    if let Some(effect) = self.effects.get_mut(id) {
+                        effect.source.state = EffectState::Playing { since: self.tick };
+                        effect.handled = effect.handled.wrapping_add(1);
                     } else {
                         error!("{:?} with wrong ID", ev);
                     }
                 }
                 Message::Stop { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
-                        effect.source.state = EffectState::Stopped
+                    if let Some(effect) = self.effects.get_mut(id) {
+                        effect.source.state = EffectState::Stopped;
+                        effect.handled = effect.handled.wrapping_add(1);
                     } else {
                         error!("{:?} with wrong ID", ev);
                     }
                 }
                 Message::Open { id, device } => {
-                    devices.insert(id, device.into());
+                    self.devices.insert(id, device.into());
                 }
                 Message::Close { id } => {
-                    devices.remove(id);
+                    self.devices.remove(id);
                 }
                 Message::SetListenerPosition { id, position } => {
-                    if let Some(device) = devices.get_mut(id) {
-                        device.position = position;
+                    if let Some(device) = self.devices.get_mut(id) {
+                        device.listener.position = position;
+                    } else {
+                        error!("{:?} with wrong ID", ev);
+                    }
+                }
+                Message::SetListenerOrientation { id, forward, up } => {
+                    if let Some(device) = self.devices.get_mut(id) {
+                        device.listener.set_orientation(forward, up);
+                    } else {
+                        error!("{:?} with wrong ID", ev);
+                    }
+                }
+                Message::SetDeviceGain { id, gain } => {
+                    if let Some(device) = self.devices.get_mut(id) {
+                        device.gain = clamp_gain(gain);
+                    } else {
+                        error!("{:?} with wrong ID", ev);
+                    }
+                }
+                Message::SetHardwareGain { id, gain } => {
+                    if let Some(device) = self.devices.get_mut(id) {
+                        if let Err(err) = device.inner.set_gain(gain) {
+                            error!("Failed to set gain of gamepad {}: {}", id, err);
+                        }
+                    } else {
+                        error!("{:?} with wrong ID", ev);
+                    }
+                }
+                Message::SetAutocenter { id, strength } => {
+                    if let Some(device) = self.devices.get_mut(id) {
+                        if let Err(err) = device.inner.set_autocenter(strength) {
+                            error!("Failed to set autocenter of gamepad {}: {}", id, err);
+                        }
                     } else {
                         error!("{:?} with wrong ID", ev);
                     }
                 }
+                Message::SetMasterGain { gain } => {
+                    self.master.gain = clamp_gain(gain);
+                }
+                Message::SetMuted { muted } => {
+                    self.master.muted = muted;
+                }
+                Message::SetPaused { paused: p } => {
+                    self.paused = p;
+                }
                 Message::HandleCloned { id } => {
-                    if let Some(effect) = effects.get_mut(id) {
+                    if let Some(effect) = self.effects.get_mut(id) {
                         effect.inc();
                     } else {
                         error!("{:?} with wrong ID", ev);
@@ -195,7 +377,7 @@ pub(crate) fn run(rx: Receiver<Message>) {
                 }
                 Message::HandleDropped { id } => {
                     let mut drop = false;
-                    if let Some(effect) = effects.get_mut(id) {
+                    if let Some(effect) = self.effects.get_mut(id) {
                         if effect.dec() == 0 {
                             drop = true;
                         }
@@ -204,98 +386,385 @@ pub(crate) fn run(rx: Receiver<Message>) {
                     }
 
                     if drop {
-                        effects.remove(id);
+                        self.effects.remove(id);
                     }
                 }
                 Message::SetGamepads { id, gamepads } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.devices = gamepads;
                     } else {
                         error!("Invalid effect id {} when changing gamepads.", id);
                     }
                 }
                 Message::AddGamepad { id, gamepad_id } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.devices.insert(gamepad_id.0, ());
                     } else {
                         error!("Invalid effect id {} when changing gamepads.", id);
                     }
                 }
                 Message::SetRepeat { id, repeat } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.repeat = repeat;
                     } else {
                         error!("Invalid effect id {} when changing repeat mode.", id);
                     }
                 }
                 Message::SetDistanceModel { id, model } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.distance_model = model;
                     } else {
                         error!("Invalid effect id {} when changing distance model.", id);
                     }
                 }
                 Message::SetPosition { id, position } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.position = position;
                     } else {
                         error!("Invalid effect id {}.", id);
                     }
                 }
                 Message::SetGain { id, gain } => {
-                    if let Some(eff) = effects.get_mut(id) {
+                    if let Some(eff) = self.effects.get_mut(id) {
                         eff.source.gain = gain;
                     } else {
                         error!("Invalid effect id {} when changing effect gain.", id);
                     }
                 }
+                Message::SetPriority { id, priority } => {
+                    if let Some(eff) = self.effects.get_mut(id) {
+                        eff.source.priority = priority;
+                    } else {
+                        error!("Invalid effect id {} when changing effect priority.", id);
+                    }
+                }
+                Message::SetDucking { id, ducking } => {
+                    if let Some(eff) = self.effects.get_mut(id) {
+                        eff.source.ducking = ducking;
+                    } else {
+                        error!("Invalid effect id {} when changing effect ducking.", id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sets motors of all devices to state at current tick and stops effects that ended.
+    pub(super) fn play(&mut self) {
+        if self.paused {
+            // Clock is frozen, keep gamepads silent until resumed.
+            let silent = Master {
+                muted: true,
+                ..self.master
+            };
+            combine_and_play(
+                &mut self.effects,
+                &mut self.devices,
+                silent,
+                self.tick,
+                self.tick_duration,
+                self.voice_limit,
+            );
+        } else {
+            combine_and_play(
+                &mut self.effects,
+                &mut self.devices,
+                self.master,
+                self.tick,
+                self.tick_duration,
+                self.voice_limit,
+            );
+            for (_, effect) in self.effects.iter_mut() {
+                effect.update_state(self.tick);
             }
         }
+    }
+
+    /// Advances clock by `elapsed`, unless it's paused.
+    pub(super) fn advance(&mut self, elapsed: Ticks) {
+        if !self.paused {
+            self.tick += elapsed;
+        }
+    }
+}
+
+pub(crate) fn run(rx: Receiver<Message>, voice_limit: Option<usize>, tick_duration: Duration) {
+    let mut server = Server::new(rx, voice_limit, tick_duration);
+    // Ticks are scheduled relative to absolute deadline, so time spent on processing and sleeping
+    // longer than requested don't accumulate.
+    let mut deadline = Instant::now();
 
-        combine_and_play(&mut effects, &mut devices, tick);
+    loop {
+        server.handle_messages();
+        server.play();
 
-        let dur = Instant::now().duration_since(t1);
-        if dur > sleep_dur {
-            // TODO: Should we add dur - sleep_dur to next iteration's dur?
+        let now = Instant::now();
+        let (next, missed) = schedule(deadline, now, tick_duration);
+        deadline = next;
+        if now < deadline {
+            thread::sleep(deadline - now);
+        } else if missed > 0 {
             warn!(
-                "One iteration of a force feedback loop took more than {}ms!",
-                TICK_DURATION
+                "Force feedback loop is late, skipping {} ticks of {}ms!",
+                missed,
+                tick_duration.as_millis()
             );
-        } else {
-            thread::sleep(sleep_dur - dur);
         }
-        tick.inc();
+
+        server.advance(Ticks(1 + missed));
+    }
+}
+
+/// Returns deadline of the next tick after tick with `deadline` and number of ticks that were
+/// missed, because `now` is already past their deadline. Missed ticks are skipped, so effects
+/// stay in sync with real time.
+fn schedule(deadline: Instant, now: Instant, tick_duration: Duration) -> (Instant, u32) {
+    let deadline = deadline + tick_duration;
+    if now < deadline {
+        return (deadline, 0);
     }
+
+    let missed = ((now - deadline).as_nanos() / tick_duration.as_nanos()) as u32;
+    (deadline + tick_duration * missed, missed)
 }
 
-pub(crate) fn init() -> Sender<Message> {
+pub(crate) fn init(voice_limit: Option<usize>, tick_duration: Duration) -> Sender<Message> {
     let (tx, _rx) = mpsc::channel();
 
     // Wasm doesn't support threads and force feedback
     #[cfg(not(target_arch = "wasm32"))]
-    thread::spawn(move || run(_rx));
+    thread::spawn(move || run(_rx, voice_limit, tick_duration));
 
     tx
 }
 
-fn combine_and_play(effects: &mut VecMap<Effect>, devices: &mut VecMap<Device>, tick: Ticks) {
+fn combine_and_play(
+    effects: &mut VecMap<Effect>,
+    devices: &mut VecMap<Device>,
+    master: Master,
+    tick: Ticks,
+    tick_duration: Duration,
+    voice_limit: Option<usize>,
+) {
     for (dev_id, dev) in devices {
-        let mut magnitude = Magnitude::zero();
-        for (_, ref mut effect) in effects.iter_mut() {
-            if effect.devices.contains_key(dev_id) {
-                magnitude += effect.combine_base_effects(tick, dev.position);
-            }
-        }
+        let magnitude = device_magnitude(
+            effects,
+            dev_id,
+            &dev.listener,
+            dev.gain,
+            master,
+            tick,
+            voice_limit,
+        );
         trace!(
             "({:?}) Setting ff state of {:?} to {:?}",
             tick,
             dev,
             magnitude
         );
-        dev.inner.set_ff_state(
-            magnitude.strong,
-            magnitude.weak,
-            Duration::from_millis(u64::from(TICK_DURATION) * 2),
+        dev.inner.set_motors(magnitude.motors(), tick_duration * 2);
+    }
+}
+
+/// Returns magnitude of effects playing on device `dev_id` with master and device `gain` applied.
+fn device_magnitude(
+    effects: &mut VecMap<Effect>,
+    dev_id: usize,
+    listener: &Listener,
+    gain: f32,
+    master: Master,
+    tick: Ticks,
+    voice_limit: Option<usize>,
+) -> Magnitude {
+    if master.muted {
+        Magnitude::zero()
+    } else {
+        mix(effects, dev_id, listener, tick, voice_limit) * (master.gain * gain)
+    }
+}
+
+/// Negative gain is treated as 0.0.
+fn clamp_gain(gain: f32) -> f32 {
+    utils::clamp(gain, 0.0, f32::MAX)
+}
+
+/// Combines effects playing on device `dev_id`, respecting their priority, ducking and
+/// `voice_limit`.
+fn mix(
+    effects: &mut VecMap<Effect>,
+    dev_id: usize,
+    listener: &Listener,
+    tick: Ticks,
+    voice_limit: Option<usize>,
+) -> Magnitude {
+    let mut voices = effects
+        .iter_mut()
+        .map(|(_, effect)| effect)
+        .filter(|effect| effect.devices.contains_key(dev_id))
+        .filter_map(|effect| match effect.state {
+            EffectState::Playing { since } => Some((since, effect)),
+            EffectState::Stopped => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Effects with higher priority go first, newer effects win with older ones of the same
+    // priority.
+    voices.sort_by(|(a_since, a), (b_since, b)| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| b_since.cmp(a_since))
+    });
+    if let Some(limit) = voice_limit {
+        voices.truncate(limit);
+    }
+
+    let mut magnitude = Magnitude::zero();
+    // Ducking of effects with higher priority than current one.
+    let mut ducking = 1.0f32;
+    // Ducking of effects with the same priority as current one.
+    let mut level_ducking = 1.0f32;
+    let mut level = None;
+    for (_, effect) in voices {
+        if level != Some(effect.priority) {
+            ducking = ducking.min(level_ducking);
+            level_ducking = 1.0;
+            level = Some(effect.priority);
+        }
+
+        magnitude += effect.combine_base_effects(tick, listener) * ducking;
+        level_ducking = level_ducking.min(effect.ducking);
+    }
+
+    magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::base_effect::{BaseEffect, BaseEffectType};
+    use super::super::Motor;
+    use super::*;
+
+    fn effect(magnitude: u16, priority: u8, ducking: f32, since: Ticks) -> Effect {
+        let base = BaseEffect {
+            kind: BaseEffectType::Strong { magnitude },
+            ..Default::default()
+        };
+        let mut devices = VecMap::new();
+        devices.insert(0, ());
+        let mut source = EffectSource::new(
+            vec![base],
+            devices,
+            Repeat::Infinitely,
+            DistanceModel::None,
+            [0.0, 0.0, 0.0],
+            1.0,
+        );
+        source.priority = priority;
+        source.ducking = ducking;
+        source.state = EffectState::Playing { since };
+
+        Effect {
+            source,
+            count: 1,
+            playing: Arc::new(PlaybackStatus::default()),
+            handled: 0,
+        }
+    }
+
+    #[test]
+    fn priority_and_ducking() {
+        let mut effects = VecMap::new();
+        effects.insert(0, effect(1000, 0, 1.0, Ticks(0)));
+        effects.insert(1, effect(2000, 0, 1.0, Ticks(1)));
+        effects.insert(2, effect(4000, 1, 0.5, Ticks(0)));
+        let mix_at = |effects: &mut VecMap<Effect>, voice_limit| {
+            mix(effects, 0, &Listener::default(), Ticks(2), voice_limit).get(Motor::Strong)
+        };
+
+        // Lower priority effects are ducked.
+        assert_eq!(mix_at(&mut effects, None), 4000 + 1500);
+        // Oldest effect with the lowest priority is skipped.
+        assert_eq!(mix_at(&mut effects, Some(2)), 4000 + 1000);
+        assert_eq!(mix_at(&mut effects, Some(1)), 4000);
+
+        effects[2].source.state = EffectState::Stopped;
+        assert_eq!(mix_at(&mut effects, Some(2)), 3000);
+        assert_eq!(mix_at(&mut effects, Some(1)), 2000);
+    }
+
+    #[test]
+    fn gain_and_mute() {
+        let (tx, rx) = mpsc::channel();
+        let mut server = Server::new(rx, None, Duration::from_millis(50));
+        server.effects.insert(0, effect(1000, 0, 1.0, Ticks(0)));
+        let magnitude = |server: &mut Server, gain| {
+            let master = server.master;
+            let listener = Listener::default();
+            device_magnitude(
+                &mut server.effects,
+                0,
+                &listener,
+                gain,
+                master,
+                Ticks(1),
+                None,
+            )
+            .get(Motor::Strong)
+        };
+
+        assert_eq!(clamp_gain(-1.0), 0.0);
+        tx.send(Message::SetMasterGain { gain: -1.0 }).unwrap();
+        server.handle_messages();
+        assert_eq!(server.master.gain, 0.0);
+        assert_eq!(magnitude(&mut server, 1.0), 0);
+
+        // Master and device gain are multiplied.
+        tx.send(Message::SetMasterGain { gain: 0.5 }).unwrap();
+        server.handle_messages();
+        assert_eq!(magnitude(&mut server, 0.5), 250);
+        assert_eq!(magnitude(&mut server, 3.0), 1500);
+        assert_eq!(magnitude(&mut server, f32::MAX), u16::MAX);
+
+        // Effects keep playing while muted.
+        tx.send(Message::SetMuted { muted: true }).unwrap();
+        server.handle_messages();
+        server.play();
+        server.advance(Ticks(1));
+        assert_eq!(magnitude(&mut server, 1.0), 0);
+        assert_eq!(
+            server.effects[0].source.state,
+            EffectState::Playing { since: Ticks(0) }
+        );
+
+        tx.send(Message::SetMuted { muted: false }).unwrap();
+        server.handle_messages();
+        assert_eq!(magnitude(&mut server, 1.0), 500);
+    }
+
+    #[test]
+    fn deadline_scheduling() {
+        let tick = Duration::from_millis(10);
+        let start = Instant::now();
+
+        assert_eq!(schedule(start, start, tick), (start + tick, 0));
+        // Late, but deadline of next tick hasn't passed yet.
+        assert_eq!(
+            schedule(start, start + Duration::from_millis(15), tick),
+            (start + tick, 0)
+        );
+        assert_eq!(
+            schedule(start, start + Duration::from_millis(35), tick),
+            (start + tick * 3, 2)
         );
+
+        // Time spent on processing doesn't accumulate.
+        let mut deadline = start;
+        for _ in 0..100 {
+            let now = deadline + Duration::from_millis(3);
+            let (next, missed) = schedule(deadline, now, tick);
+            assert_eq!(missed, 0);
+            deadline = next;
+        }
+        assert_eq!(deadline, start + tick * 100);
     }
 }
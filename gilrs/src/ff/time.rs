@@ -10,22 +10,36 @@ use std::time::Duration;
 
 use crate::utils;
 
+/// Default duration of one tick in milliseconds.
 pub(crate) const TICK_DURATION: u32 = 50;
 
+/// Converts duration of one tick to milliseconds. Returns at least 1.
+pub(crate) fn tick_ms(tick_duration: Duration) -> u32 {
+    tick_duration.as_millis().min(u128::from(u32::MAX)).max(1) as u32
+}
+
 /// Represents duration.
 ///
 /// This type is only useful as input parameter for other functions in force feedback module. To
-/// create it, use `from_ms()` method. Keep in mind that `Ticks` **is not precise** representation
-/// of time.
+/// create it, use [`Gilrs::ff_ticks()`][ff_ticks], which takes into account
+/// [tick duration][set] of force feedback loop. Keep in mind that `Ticks` **is not precise**
+/// representation of time.
+///
+/// `From<Duration>` always assumes that one tick lasts 50 ms and gives wrong results if `Gilrs`
+/// was created with different tick duration.
+///
+/// [set]: ../struct.GilrsBuilder.html#method.set_ff_tick_duration
+/// [ff_ticks]: ../struct.Gilrs.html#method.ff_ticks
 ///
 /// # Example
 ///
 /// ```rust
-/// use gilrs::ff::Ticks;
+/// use gilrs::Gilrs;
 /// use std::time::Duration;
 ///
-/// let t1 = Ticks::from_ms(110);
-/// let t2 = Ticks::from(Duration::from_millis(130));
+/// let gilrs = Gilrs::new().unwrap();
+/// let t1 = gilrs.ff_ticks(Duration::from_millis(110));
+/// let t2 = gilrs.ff_ticks(Duration::from_millis(130));
 ///
 /// /// `Ticks` is not precise.
 /// assert_eq!(t1, t2);
@@ -34,12 +48,17 @@ pub(crate) const TICK_DURATION: u32 = 50;
 pub struct Ticks(pub(super) u32);
 
 impl Ticks {
+    #[deprecated(
+        since = "0.8.0",
+        note = "assumes that tick lasts 50 ms, use `Gilrs::ff_ticks()` instead"
+    )]
     pub fn from_ms(dur: u32) -> Self {
-        Ticks(utils::ceil_div(dur, TICK_DURATION))
+        Ticks::from_ms_with(dur, TICK_DURATION)
     }
 
-    pub(super) fn inc(&mut self) {
-        self.0 += 1
+    /// Converts `dur` to ticks that last `tick_duration` milliseconds.
+    pub(crate) fn from_ms_with(dur: u32, tick_duration: u32) -> Self {
+        Ticks(utils::ceil_div(dur, tick_duration))
     }
 
     pub(super) fn checked_sub(self, rhs: Ticks) -> Option<Ticks> {
@@ -49,7 +68,10 @@ impl Ticks {
 
 impl From<Duration> for Ticks {
     fn from(dur: Duration) -> Self {
-        Ticks::from_ms(dur.as_secs() as u32 * 1000 + dur.subsec_millis())
+        Ticks::from_ms_with(
+            dur.as_secs() as u32 * 1000 + dur.subsec_millis(),
+            TICK_DURATION,
+        )
     }
 }
 
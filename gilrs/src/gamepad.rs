@@ -11,24 +11,32 @@ use crate::{
         Axis, AxisOrBtn, Button, Code, Event, EventType,
     },
     ff::{
+        self,
         server::{self, Message},
-        Error as FfError,
+        Capabilities, Error as FfError, Motor, Ticks,
     },
-    mapping::{Mapping, MappingData, MappingDb},
-    utils, MappingError,
+    mapping::{self, AxisRange, Mapping, MappingData, MappingDb, MappingMatch, Target},
+    record::{Recording, Replay, ReplayTiming},
+    utils,
+    virtual_gamepad::Injector,
+    MappingError,
 };
 
 use gilrs_core::{
     self, AxisInfo, Error as PlatformError, Event as RawEvent, EventType as RawEventType,
 };
 
+use fnv::FnvHashMap;
 use uuid::Uuid;
 
 use std::{
     collections::VecDeque,
     error,
     fmt::{self, Display},
+    path::PathBuf,
     sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
 pub use gilrs_core::PowerInfo;
@@ -36,6 +44,16 @@ pub use gilrs_core::PowerInfo;
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "async")]
+use crate::stream::EventWaker;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
 const DEFAULT_DEADZONE: f32 = 0.1;
 
 /// Main object responsible of managing gamepads.
@@ -132,14 +150,21 @@ pub struct Gilrs {
     inner: gilrs_core::Gilrs,
     next_id: usize,
     tx: Sender<Message>,
+    /// Duration of force feedback tick in milliseconds.
+    ff_tick_duration: u32,
     counter: u64,
     mappings: MappingDb,
+    mappings_reload_interval: Option<Duration>,
+    last_mappings_reload: Instant,
     default_filters: bool,
     events: VecDeque<Event>,
     axis_to_btn_pressed: f32,
     axis_to_btn_released: f32,
     update_state: bool,
     gamepads_data: Vec<GamepadData>,
+    replay: Option<Box<Replay>>,
+    #[cfg(feature = "async")]
+    event_waker: Option<EventWaker>,
 }
 
 impl Gilrs {
@@ -150,16 +175,67 @@ impl Gilrs {
     }
 
     /// Returns next pending event. If there is no pending event, `None` is
-    /// returned. This function will not block current thread and should be safe
-    /// to call in async context.
+    /// returned. This function will not wait for events.
+    ///
+    /// If mappings are loaded from files (see
+    /// [`GilrsBuilder::add_mappings_path()`](struct.GilrsBuilder.html#method.add_mappings_path)),
+    /// it periodically checks them for changes, which is blocking file system I/O.
     pub fn next_event(&mut self) -> Option<Event> {
+        self.next_event_inner(false, None)
+    }
+
+    /// Returns next pending event. If there is no pending event, blocks current thread until
+    /// there is one or `timeout` elapses. If `timeout` is `None`, waits indefinitely.
+    ///
+    /// On Linux, waiting is done with `epoll` on gamepads' and udev monitor's file descriptors, so
+    /// it doesn't use CPU. On Windows and macOS this function waits for event from backend's
+    /// thread. On platforms without gamepad support, it only sleeps until `timeout`. On wasm it
+    /// never blocks.
+    ///
+    /// ```no_run
+    /// use gilrs::Gilrs;
+    /// use std::time::Duration;
+    ///
+    /// let mut gilrs = Gilrs::new().unwrap();
+    ///
+    /// loop {
+    ///     match gilrs.next_event_blocking(Some(Duration::from_secs(1))) {
+    ///         Some(ev) => println!("{:?}", ev),
+    ///         None => println!("No input in last second"),
+    ///     }
+    /// }
+    /// ```
+    pub fn next_event_blocking(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        if cfg!(target_arch = "wasm32") {
+            return self.next_event();
+        }
+
+        let deadline = timeout.map(|t| Instant::now() + t);
+        self.next_event_inner(true, deadline)
+    }
+
+    fn next_event_inner(&mut self, blocking: bool, deadline: Option<Instant>) -> Option<Event> {
         use crate::ev::filter::{axis_dpad_to_button, deadzone, Filter, Jitter};
 
+        if self.replay.is_some() {
+            return if blocking {
+                self.next_replayed_event_blocking(deadline)
+            } else {
+                self.next_replayed_event()
+            };
+        }
+
+        if let Some(interval) = self.mappings_reload_interval {
+            if self.last_mappings_reload.elapsed() >= interval {
+                self.reload_mappings();
+            }
+        }
+
         let ev = if self.default_filters {
             let jitter_filter = Jitter::new();
             loop {
                 let ev = self
-                    .next_event_priv()
+                    .next_event_priv(blocking, deadline)
                     .filter_ev(&axis_dpad_to_button, self)
                     .filter_ev(&jitter_filter, self)
                     .filter_ev(&deadzone, self);
@@ -171,7 +247,13 @@ impl Gilrs {
                 }
             }
         } else {
-            self.next_event_priv()
+            // Mapping layer drops events of elements whose value didn't change.
+            loop {
+                match self.next_event_priv(blocking, deadline) {
+                    Some(ev) if ev.is_dropped() => (),
+                    ev => break ev,
+                }
+            }
         };
 
         if self.update_state {
@@ -183,12 +265,97 @@ impl Gilrs {
         ev
     }
 
+    /// Returns next recorded event. Recorded events were already filtered, so they are returned
+    /// unchanged.
+    fn next_replayed_event(&mut self) -> Option<Event> {
+        let ev = self
+            .events
+            .pop_front()
+            .or_else(|| self.replay.as_mut().and_then(|replay| replay.next_event()))?;
+        let id = ev.id;
+
+        match ev.event {
+            EventType::Connected | EventType::Disconnected if id.0 < self.gamepads_data.len() => {
+                let injector = self.replay.as_ref().unwrap().injector();
+
+                if ev.event == EventType::Connected {
+                    injector.connect(id);
+                } else {
+                    injector.disconnect(id);
+                }
+
+                // Update connection state of virtual gamepad.
+                while self.inner.next_event().is_some() {}
+
+                if ev.event == EventType::Connected {
+                    self.gamepads_data[id.0] = GamepadData::new(
+                        id,
+                        self.tx.clone(),
+                        self.inner.gamepad(id.0).unwrap(),
+                        &self.mappings,
+                    );
+                } else {
+                    let _ = self.tx.send(Message::Close { id: id.0 });
+                }
+            }
+            _ => (),
+        }
+
+        if self.update_state {
+            self.update(&ev);
+        }
+
+        Some(ev)
+    }
+
+    fn next_replayed_event_blocking(&mut self, deadline: Option<Instant>) -> Option<Event> {
+        loop {
+            if let Some(ev) = self.next_replayed_event() {
+                return Some(ev);
+            }
+
+            // `None` if all events were replayed
+            let mut wait = self
+                .replay
+                .as_ref()
+                .and_then(|replay| replay.time_to_next_event())?;
+
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+
+                wait = wait.min(deadline - now);
+            }
+
+            thread::sleep(wait);
+        }
+    }
+
+    /// Returns true if `Gilrs` was created with
+    /// [`GilrsBuilder::build_replay()`](struct.GilrsBuilder.html#method.build_replay) and not all
+    /// recorded events were returned yet.
+    pub fn is_replaying(&self) -> bool {
+        match self.replay {
+            Some(ref replay) => !replay.is_finished(),
+            None => false,
+        }
+    }
+
     /// Returns next pending event.
-    fn next_event_priv(&mut self) -> Option<Event> {
+    fn next_event_priv(&mut self, blocking: bool, deadline: Option<Instant>) -> Option<Event> {
         if let Some(ev) = self.events.pop_front() {
             Some(ev)
         } else {
-            match self.inner.next_event() {
+            let raw = if blocking {
+                let timeout = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+                self.inner.next_event_blocking(timeout)
+            } else {
+                self.inner.next_event()
+            };
+
+            match raw {
                 Some(RawEvent { id, event, time }) => {
                     trace!("Original event: {:?}", RawEvent { id, event, time });
                     let id = GamepadId(id);
@@ -196,6 +363,10 @@ impl Gilrs {
                     let event = match event {
                         RawEventType::ButtonPressed(nec) => {
                             let nec = Code(nec);
+                            if !self.gamepad(id).data.has_plain_mapping(nec) {
+                                return self.mapped_events(id, time, nec, 1.0);
+                            }
+
                             match self.gamepad(id).axis_or_btn_name(nec) {
                                 Some(AxisOrBtn::Btn(b)) => {
                                     self.events.push_back(Event {
@@ -220,6 +391,10 @@ impl Gilrs {
                         }
                         RawEventType::ButtonReleased(nec) => {
                             let nec = Code(nec);
+                            if !self.gamepad(id).data.has_plain_mapping(nec) {
+                                return self.mapped_events(id, time, nec, -1.0);
+                            }
+
                             match self.gamepad(id).axis_or_btn_name(nec) {
                                 Some(AxisOrBtn::Btn(b)) => {
                                     self.events.push_back(Event {
@@ -247,6 +422,11 @@ impl Gilrs {
                             let axis_info = *self.gamepad(id).inner.axis_info(nec).unwrap();
                             let nec = Code(nec);
 
+                            if !self.gamepad(id).data.has_plain_mapping(nec) {
+                                let val = axis_value(&axis_info, val, Axis::Unknown);
+                                return self.mapped_events(id, time, nec, val);
+                            }
+
                             match self.gamepad(id).axis_or_btn_name(nec) {
                                 Some(AxisOrBtn::Btn(b)) => {
                                     let val = btn_value(&axis_info, val);
@@ -325,6 +505,63 @@ impl Gilrs {
         }
     }
 
+    /// Queues events for every element that `nec` is mapped to and returns the first one. `val`
+    /// is value of `nec` in range [-1.0, 1.0], buttons use 1.0 when pressed and -1.0 when
+    /// released. Returns `Dropped` event if value of no element has changed.
+    fn mapped_events(
+        &mut self,
+        id: GamepadId,
+        time: SystemTime,
+        nec: Code,
+        val: f32,
+    ) -> Option<Event> {
+        let (pressed_threshold, released_threshold) =
+            (self.axis_to_btn_pressed, self.axis_to_btn_released);
+        let data = &mut self.gamepads_data[id.0];
+        let mut targets: Vec<_> = data
+            .mapping
+            .targets(&nec.0)
+            .iter()
+            .map(|target| (target.to, target_value(target, val)))
+            .collect();
+        // Handle released buttons first, so when axis moves from one half to the other, button of
+        // the previous half is released before the next one is pressed.
+        targets.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let events = &mut self.events;
+        for (to, val) in targets {
+            let mut push = |event| events.push_back(Event { id, time, event });
+            let state = data.targets.entry((nec, to)).or_default();
+            match to {
+                AxisOrBtn::Axis(a) => {
+                    if state.value != val {
+                        push(EventType::AxisChanged(a, val, nec));
+                    }
+                }
+                AxisOrBtn::Btn(b) => {
+                    if val >= pressed_threshold && !state.pressed {
+                        state.pressed = true;
+                        push(EventType::ButtonPressed(b, nec));
+                    } else if val <= released_threshold && state.pressed {
+                        state.pressed = false;
+                        push(EventType::ButtonReleased(b, nec));
+                    }
+
+                    if state.value != val {
+                        push(EventType::ButtonChanged(b, val, nec));
+                    }
+                }
+            }
+            state.value = val;
+        }
+
+        Some(events.pop_front().unwrap_or(Event {
+            id,
+            time,
+            event: EventType::Dropped,
+        }))
+    }
+
     /// Updates internal state according to `event`.
     ///
     /// Please note, that it's not necessary to call this function unless you modify events by using
@@ -383,6 +620,48 @@ impl Gilrs {
         self.counter = 0;
     }
 
+    /// Reloads mappings from files and directories added with
+    /// [`GilrsBuilder::add_mappings_path()`](struct.GilrsBuilder.html#method.add_mappings_path)
+    /// and applies them to connected gamepads. Returns `true` if mapping of any connected gamepad
+    /// has changed.
+    ///
+    /// Files are only read again if they were added, removed or modified since last reload. This
+    /// is also done automatically by `next_event()`, see
+    /// [`GilrsBuilder::set_mappings_reload_interval()`](struct.GilrsBuilder.html#method.set_mappings_reload_interval).
+    ///
+    /// Gamepads with mapping set by [`set_mapping()`](#method.set_mapping) keep it until they are
+    /// reconnected. State of gamepads whose mapping has changed is reset.
+    pub fn reload_mappings(&mut self) -> bool {
+        self.last_mappings_reload = Instant::now();
+
+        let changed = self.mappings.reload();
+        if changed.is_empty() {
+            return false;
+        }
+
+        info!("Reloaded mappings, {} of them changed.", changed.len());
+
+        let mut applied = false;
+        for data in &mut self.gamepads_data {
+            if data.user_mapping {
+                continue;
+            }
+
+            let gamepad = match self.inner.gamepad(data.id.0) {
+                Some(gamepad) if gamepad.is_connected() => gamepad,
+                _ => continue,
+            };
+
+            let uuid = Uuid::from_bytes(gamepad.uuid());
+            if changed.iter().any(|&other| mapping::can_match(uuid, other)) {
+                data.set_mapping(find_mapping(gamepad, &self.mappings));
+                applied = true;
+            }
+        }
+
+        applied
+    }
+
     fn finish_gamepads_creation(&mut self) {
         let tx = self.tx.clone();
         for id in 0..self.inner.last_gamepad_hint() {
@@ -461,10 +740,65 @@ impl Gilrs {
         self.events.push_back(ev);
     }
 
+    /// Changes gain applied to force feedback of all gamepads, after effects are combined.
+    /// `gain` will be clamped to \[0.0, f32::MAX\]. Defaults to 1.0.
+    pub fn set_ff_gain(&self, gain: f32) -> Result<(), FfError> {
+        self.tx.send(Message::SetMasterGain { gain })?;
+
+        Ok(())
+    }
+
+    /// Mutes or unmutes force feedback of all gamepads. Effects continue to play while muted, but
+    /// their magnitude is not sent to gamepads.
+    pub fn set_ff_muted(&self, muted: bool) -> Result<(), FfError> {
+        self.tx.send(Message::SetMuted { muted })?;
+
+        Ok(())
+    }
+
+    /// Pauses all force feedback effects. Gamepads stop vibrating at once and time used by
+    /// effects doesn't advance until [`resume_ff()`](#method.resume_ff) is called, so effects
+    /// continue from the same point. Effects can be played and stopped while paused.
+    pub fn pause_ff(&self) -> Result<(), FfError> {
+        self.tx.send(Message::SetPaused { paused: true })?;
+
+        Ok(())
+    }
+
+    /// Resumes force feedback effects paused by [`pause_ff()`](#method.pause_ff).
+    pub fn resume_ff(&self) -> Result<(), FfError> {
+        self.tx.send(Message::SetPaused { paused: false })?;
+
+        Ok(())
+    }
+
+    /// Converts `duration` to [`Ticks`](ff/struct.Ticks.html) of force feedback loop of this
+    /// `Gilrs`. Unlike `Ticks::from(duration)`, it takes into account tick duration set with
+    /// [`GilrsBuilder::set_ff_tick_duration()`](struct.GilrsBuilder.html#method.set_ff_tick_duration).
+    pub fn ff_ticks(&self, duration: Duration) -> Ticks {
+        let ms = duration.as_millis().min(u128::from(u32::MAX)) as u32;
+        Ticks::from_ms_with(ms, self.ff_tick_duration)
+    }
+
+    /// Returns duration of one tick of force feedback loop, rounded down to whole milliseconds.
+    /// Pass it to [`RumbleClip::to_base_effects()`](ff/struct.RumbleClip.html#method.to_base_effects)
+    /// and [`RumbleClip::length()`](ff/struct.RumbleClip.html#method.length).
+    pub fn ff_tick_duration(&self) -> Duration {
+        Duration::from_millis(self.ff_tick_duration.into())
+    }
+
     pub(crate) fn ff_sender(&self) -> &Sender<Message> {
         &self.tx
     }
 
+    pub(crate) fn ff_tick_ms(&self) -> u32 {
+        self.ff_tick_duration
+    }
+
+    pub(crate) fn last_gamepad_hint(&self) -> usize {
+        self.inner.last_gamepad_hint()
+    }
+
     /// Sets gamepad's mapping and returns SDL2 representation of them. Returned mappings may not be
     /// compatible with SDL2 - if it is important, use
     /// [`set_mapping_strict()`](#method.set_mapping_strict).
@@ -482,6 +816,9 @@ impl Gilrs {
     ///
     /// Error is also returned if this function is not implemented or gamepad is not connected.
     ///
+    /// State of the gamepad is reset. Mapping set by this function is not replaced by
+    /// [`reload_mappings()`](#method.reload_mappings) until gamepad is reconnected.
+    ///
     /// # Example
     ///
     /// ```
@@ -506,7 +843,7 @@ impl Gilrs {
         name: O,
     ) -> Result<String, MappingError> {
         if let Some(gamepad) = self.inner.gamepad(gamepad_id) {
-            if gamepad.is_connected() {
+            if !gamepad.is_connected() {
                 return Err(MappingError::NotConnected);
             }
 
@@ -525,7 +862,8 @@ impl Gilrs {
 
             // We checked if gamepad is connected, so it should never panic
             let data = &mut self.gamepads_data[gamepad_id];
-            data.mapping = mapping;
+            data.set_mapping(mapping);
+            data.user_mapping = true;
 
             Ok(s)
         } else {
@@ -546,10 +884,10 @@ impl Gilrs {
         mapping: &MappingData,
         name: O,
     ) -> Result<String, MappingError> {
-        if mapping.button(Button::C).is_some()
-            || mapping.button(Button::Z).is_some()
-            || mapping.axis(Axis::LeftZ).is_some()
-            || mapping.axis(Axis::RightZ).is_some()
+        if mapping.button_input(Button::C).is_some()
+            || mapping.button_input(Button::Z).is_some()
+            || mapping.has_axis(Axis::LeftZ)
+            || mapping.has_axis(Axis::RightZ)
         {
             Err(MappingError::NotSdl2Compatible)
         } else {
@@ -568,6 +906,50 @@ impl Gilrs {
     }
 }
 
+/// Stream of events, available with `async` feature.
+///
+/// Stream ends only when `Gilrs` was created with
+/// [`GilrsBuilder::build_replay()`](struct.GilrsBuilder.html#method.build_replay) and all recorded
+/// events were returned. On first `Pending`, a thread that waits for new events (or for the next
+/// replayed event) is spawned. On wasm the task is woken immediately, so stream is effectively
+/// polled in a loop.
+///
+/// Polling may check mapping files for changes, which blocks, see
+/// [`GilrsBuilder::set_mappings_reload_interval()`](struct.GilrsBuilder.html#method.set_mappings_reload_interval).
+#[cfg(feature = "async")]
+impl Stream for Gilrs {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let gilrs = self.get_mut();
+
+        if let Some(ev) = gilrs.next_event() {
+            return Poll::Ready(Some(ev));
+        }
+
+        let deadline = match gilrs.replay {
+            Some(ref replay) => match replay.time_to_next_event() {
+                Some(delay) => Some(Instant::now() + delay),
+                None => return Poll::Ready(None),
+            },
+            None => None,
+        };
+
+        if cfg!(target_arch = "wasm32") {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let inner = &gilrs.inner;
+        gilrs
+            .event_waker
+            .get_or_insert_with(|| EventWaker::new(inner.waiter()))
+            .register(cx.waker().clone(), deadline);
+
+        Poll::Pending
+    }
+}
+
 /// Allow to create `Gilrs ` with customized behaviour.
 pub struct GilrsBuilder {
     mappings: MappingDb,
@@ -577,6 +959,9 @@ pub struct GilrsBuilder {
     update_state: bool,
     env_mappings: bool,
     included_mappings: bool,
+    mappings_reload_interval: Option<Duration>,
+    ff_voice_limit: Option<usize>,
+    ff_tick_duration: Duration,
 }
 
 impl GilrsBuilder {
@@ -590,6 +975,9 @@ impl GilrsBuilder {
             update_state: true,
             env_mappings: true,
             included_mappings: true,
+            mappings_reload_interval: Some(Duration::from_secs(1)),
+            ff_voice_limit: None,
+            ff_tick_duration: Duration::from_millis(ff::TICK_DURATION.into()),
         }
     }
 
@@ -625,6 +1013,35 @@ impl GilrsBuilder {
         self
     }
 
+    /// Adds SDL mappings from file or directory. If `path` is a directory, all `.txt` files
+    /// directly inside it are loaded in alphabetical order. Path doesn't have to exist when
+    /// `Gilrs` is created.
+    ///
+    /// Mappings from these files take precedence over all other mappings. Files are watched for
+    /// changes and when one of them is added, removed or modified, mappings are reloaded and
+    /// applied to connected gamepads. See
+    /// [`set_mappings_reload_interval()`](#method.set_mappings_reload_interval).
+    pub fn add_mappings_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.mappings.add_path(path.into());
+
+        self
+    }
+
+    /// Sets how often `Gilrs::next_event()` checks if files added with
+    /// [`add_mappings_path()`](#method.add_mappings_path) have changed. If `None`, mappings are
+    /// only reloaded by [`Gilrs::reload_mappings()`](struct.Gilrs.html#method.reload_mappings).
+    /// Defaults to 1 second. Files are never checked if no path was added or when events are
+    /// replayed.
+    ///
+    /// Checking files is blocking file system I/O done inside `next_event()` (and when polling
+    /// `Gilrs` as `Stream`). If that's not acceptable, set it to `None` and call
+    /// `reload_mappings()` where blocking is allowed.
+    pub fn set_mappings_reload_interval<D: Into<Option<Duration>>>(mut self, interval: D) -> Self {
+        self.mappings_reload_interval = interval.into();
+
+        self
+    }
+
     /// Sets values on which `ButtonPressed` and `ButtonReleased` events will be emitted. `build()`
     /// will return error if `pressed ≤ released` or if one of values is outside [0.0, 1.0].
     ///
@@ -644,8 +1061,92 @@ impl GilrsBuilder {
         self
     }
 
+    /// Sets maximum number of force feedback effects that are played at the same time on one
+    /// gamepad. If more effects are playing, ones with the lowest
+    /// [priority](ff/struct.EffectBuilder.html#method.priority) are skipped. Defaults to no limit.
+    pub fn set_ff_voice_limit(mut self, limit: usize) -> Self {
+        self.ff_voice_limit = Some(limit);
+
+        self
+    }
+
+    /// Sets how often force feedback effects are updated. Duration is rounded down to whole
+    /// milliseconds and `build()` will return error if it's shorter than 1 ms. Defaults to 50 ms.
+    ///
+    /// This also changes duration of [`Ticks`](ff/struct.Ticks.html) used by effects of created
+    /// `Gilrs`. Use [`Gilrs::ff_ticks()`](struct.Gilrs.html#method.ff_ticks) to create them.
+    pub fn set_ff_tick_duration(mut self, duration: Duration) -> Self {
+        self.ff_tick_duration = duration;
+
+        self
+    }
+
     /// Creates `Gilrs`.
     pub fn build(mut self) -> Result<Gilrs, Error> {
+        if let Some(err) = self.prepare() {
+            return Err(err);
+        }
+
+        let mut is_dummy = false;
+        let inner = match gilrs_core::Gilrs::new() {
+            Ok(g) => g,
+            Err(PlatformError::NotImplemented(g)) => {
+                is_dummy = true;
+
+                g
+            }
+            Err(PlatformError::Other(e)) => return Err(Error::Other(e)),
+        };
+
+        let gilrs = self.finish(inner);
+
+        if is_dummy {
+            Err(Error::NotImplemented(gilrs))
+        } else {
+            Ok(gilrs)
+        }
+    }
+
+    /// Creates `Gilrs` that doesn't use any real device. Gamepads are added and their input is
+    /// injected with returned [`Injector`](virtual_gamepad/struct.Injector.html). See
+    /// [`virtual_gamepad`](virtual_gamepad/index.html) module for details.
+    pub fn build_virtual(mut self) -> Result<(Gilrs, Injector), Error> {
+        if let Some(err) = self.prepare() {
+            return Err(err);
+        }
+
+        let (inner, injector) = gilrs_core::Gilrs::new_virtual();
+
+        Ok((self.finish(inner), Injector::new(injector)))
+    }
+
+    /// Creates `Gilrs` that returns events from `recording` instead of real devices. Recorded
+    /// gamepads are recreated with the same IDs and events are returned from `next_event()`
+    /// exactly as they were recorded – they are not filtered again. See
+    /// [`record`](record/index.html) module for details.
+    pub fn build_replay(
+        mut self,
+        recording: Recording,
+        timing: ReplayTiming,
+    ) -> Result<Gilrs, Error> {
+        if let Some(err) = self.prepare() {
+            return Err(err);
+        }
+
+        let (mut inner, injector) = gilrs_core::Gilrs::new_virtual();
+        let replay = Replay::new(recording, timing, Injector::new(injector));
+
+        // Create recorded gamepads before `GamepadData` is created for them.
+        while inner.next_event().is_some() {}
+
+        let mut gilrs = self.finish(inner);
+        gilrs.replay = Some(Box::new(replay));
+
+        Ok(gilrs)
+    }
+
+    /// Loads mappings and returns error if options are invalid.
+    fn prepare(&mut self) -> Option<Error> {
         if self.included_mappings {
             self.mappings.add_included_mappings();
         }
@@ -654,48 +1155,62 @@ impl GilrsBuilder {
             self.mappings.add_env_mappings();
         }
 
+        self.mappings.reload();
+
         debug!("Loaded {} mappings.", self.mappings.len());
 
-        if self.axis_to_btn_pressed <= self.axis_to_btn_released
-            || self.axis_to_btn_pressed < 0.0
-            || self.axis_to_btn_pressed > 1.0
-            || self.axis_to_btn_released < 0.0
-            || self.axis_to_btn_released > 1.0
+        if !(self.axis_to_btn_pressed > self.axis_to_btn_released
+            && self.axis_to_btn_pressed >= 0.0
+            && self.axis_to_btn_pressed <= 1.0
+            && self.axis_to_btn_released >= 0.0
+            && self.axis_to_btn_released <= 1.0)
         {
-            return Err(Error::InvalidAxisToBtn);
+            return Some(Error::InvalidAxisToBtn);
         }
 
-        let mut is_dummy = false;
-        let inner = match gilrs_core::Gilrs::new() {
-            Ok(g) => g,
-            Err(PlatformError::NotImplemented(g)) => {
-                is_dummy = true;
+        if self.ff_tick_duration.as_millis() == 0
+            || self.ff_tick_duration.as_millis() > u128::from(u32::MAX)
+        {
+            return Some(Error::InvalidFfTickDuration);
+        }
 
-                g
-            }
-            Err(PlatformError::Other(e)) => return Err(Error::Other(e)),
+        None
+    }
+
+    fn finish(self, inner: gilrs_core::Gilrs) -> Gilrs {
+        let ff_tick_duration = self.ff_tick_duration.as_millis() as u32;
+        // There is nothing to reload.
+        let mappings_reload_interval = if self.mappings.has_paths() {
+            self.mappings_reload_interval
+        } else {
+            None
         };
 
         let mut gilrs = Gilrs {
             inner,
             next_id: 0,
-            tx: server::init(),
+            tx: server::init(
+                self.ff_voice_limit,
+                Duration::from_millis(ff_tick_duration.into()),
+            ),
+            ff_tick_duration,
             counter: 0,
             mappings: self.mappings,
+            mappings_reload_interval,
+            last_mappings_reload: Instant::now(),
             default_filters: self.default_filters,
             events: VecDeque::new(),
             axis_to_btn_pressed: self.axis_to_btn_pressed,
             axis_to_btn_released: self.axis_to_btn_released,
             update_state: self.update_state,
             gamepads_data: Vec::new(),
+            replay: None,
+            #[cfg(feature = "async")]
+            event_waker: None,
         };
         gilrs.finish_gamepads_creation();
 
-        if is_dummy {
-            Err(Error::NotImplemented(gilrs))
-        } else {
-            Ok(gilrs)
-        }
+        gilrs
     }
 }
 
@@ -735,7 +1250,7 @@ impl<'a> Iterator for ConnectedGamepadsIterator<'a> {
 #[derive(Debug, Copy, Clone)]
 pub struct Gamepad<'a> {
     data: &'a GamepadData,
-    inner: &'a gilrs_core::Gamepad,
+    inner: gilrs_core::Gamepad<'a>,
 }
 
 impl<'a> Gamepad<'a> {
@@ -841,11 +1356,88 @@ impl<'a> Gamepad<'a> {
         }
     }
 
+    /// Returns current mapping of gamepad as SDL_GameControllerDB line. It includes axis ranges,
+    /// inversion and hats, and can be used by SDL or added back with
+    /// [`GilrsBuilder::add_mappings()`](struct.GilrsBuilder.html#method.add_mappings).
+    ///
+    /// Elements that can't be represented in SDL format, for example `Axis::DPadX` mapped to
+    /// something other than hat, are skipped.
+    pub fn mapping_string(&self) -> String {
+        self.data.mapping.to_sdl_string(
+            Uuid::from_bytes(self.uuid()),
+            self.name(),
+            self.inner.buttons(),
+            self.inner.axes(),
+        )
+    }
+
+    /// Returns rule that was used to find gamepad's SDL mapping in mapping database, or `None` if
+    /// gamepad doesn't use mapping from database (for example it uses default mapping or one set
+    /// by [`Gilrs::set_mapping()`](struct.Gilrs.html#method.set_mapping)).
+    pub fn mapping_match(&self) -> Option<MappingMatch> {
+        self.data.mapping.db_match()
+    }
+
     /// Returns true if force feedback is supported by device.
     pub fn is_ff_supported(&self) -> bool {
         self.inner.is_ff_supported()
     }
 
+    /// Returns vibration motors that can be used by force feedback effects. Currently only
+    /// `Motor::Strong` and `Motor::Weak` are reported; no platform drives trigger motors yet.
+    pub fn ff_motors(&self) -> &[Motor] {
+        self.inner.ff_motors()
+    }
+
+    /// Sets gain of the gamepad's hardware, which scales strength of all effects played by
+    /// device. `gain` is in range from 0 to `u16::MAX` (full strength). Unlike
+    /// [`set_ff_gain()`](#method.set_ff_gain) it's applied by device itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FfNotSupported(id)` if changing gain is not supported by gamepad (see
+    /// [`ff_capabilities()`](#method.ff_capabilities)).
+    pub fn set_ff_hardware_gain(&self, gain: u16) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() || !self.ff_capabilities().gain {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::SetHardwareGain {
+                id: self.data.id.0,
+                gain,
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Sets strength of gamepad's autocenter spring, used mostly by racing wheels. 0 disables
+    /// autocenter.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FfNotSupported(id)` if autocenter is not supported by gamepad (see
+    /// [`ff_capabilities()`](#method.ff_capabilities)).
+    pub fn set_ff_autocenter(&self, strength: u16) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() || !self.ff_capabilities().autocenter {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::SetAutocenter {
+                id: self.data.id.0,
+                strength,
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Returns force feedback effects and features supported by device, like number of effects
+    /// it can hold or whether gain and autocenter can be changed.
+    pub fn ff_capabilities(&self) -> Capabilities {
+        Capabilities::from_native(self.inner.ff_capabilities())
+    }
+
     /// Change gamepad position used by force feedback effects.
     pub fn set_listener_position<Vec3: Into<[f32; 3]>>(
         &self,
@@ -864,6 +1456,47 @@ impl<'a> Gamepad<'a> {
         }
     }
 
+    /// Change gamepad orientation used by force feedback effects. `forward` and `up` should be
+    /// perpendicular. When orientation is set, effects on the left of the gamepad are moved to
+    /// the strong (left) motor and effects on the right to the weak (right) motor. By default
+    /// effects are not panned.
+    pub fn set_listener_orientation<Vec3: Into<[f32; 3]>>(
+        &self,
+        forward: Vec3,
+        up: Vec3,
+    ) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::SetListenerOrientation {
+                id: self.data.id.0,
+                forward: forward.into(),
+                up: up.into(),
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Changes gain applied to force feedback of this gamepad after effects are combined. It's
+    /// multiplied by gain set with [`Gilrs::set_ff_gain()`](struct.Gilrs.html#method.set_ff_gain).
+    /// `gain` will be clamped to \[0.0, f32::MAX\]. Gain is reset to 1.0 when gamepad is
+    /// disconnected.
+    pub fn set_ff_gain(&self, gain: f32) -> Result<(), FfError> {
+        if !self.is_connected() {
+            Err(FfError::Disconnected(self.id()))
+        } else if !self.is_ff_supported() {
+            Err(FfError::FfNotSupported(self.id()))
+        } else {
+            self.data.tx.send(Message::SetDeviceGain {
+                id: self.data.id.0,
+                gain,
+            })?;
+            Ok(())
+        }
+    }
+
     /// Returns `AxisOrBtn` mapped to `Code`.
     pub fn axis_or_btn_name(&self, ec: Code) -> Option<AxisOrBtn> {
         self.data.axis_or_btn_name(ec)
@@ -902,27 +1535,47 @@ impl<'a> Gamepad<'a> {
     pub(crate) fn mapping(&self) -> &Mapping {
         &self.data.mapping
     }
+
+    /// Returns `false` if `ec` is mapped to more than one element or its value has to be
+    /// transformed. State of such elements is kept by mapping layer, not in `state()`.
+    pub(crate) fn has_plain_mapping(&self, ec: Code) -> bool {
+        self.data.has_plain_mapping(ec)
+    }
+
+    pub(crate) fn inner(&self) -> gilrs_core::Gamepad<'a> {
+        self.inner
+    }
 }
 
 #[derive(Debug)]
 struct GamepadData {
     state: GamepadState,
     mapping: Mapping,
+    /// State of elements mapped from codes that don't have plain mapping. Unlike `state`, it's
+    /// kept for each element, so halves of axis mapped to buttons are pressed independently.
+    targets: FnvHashMap<(Code, AxisOrBtn), TargetState>,
+    /// Mapping was set by `Gilrs::set_mapping()` and shouldn't be reloaded.
+    user_mapping: bool,
     tx: Sender<Message>,
     id: GamepadId,
 }
 
+/// State of element updated by `Gilrs::mapped_events()`, regardless of
+/// `GilrsBuilder::set_update_state()`.
+#[derive(Copy, Clone, Debug, Default)]
+struct TargetState {
+    pressed: bool,
+    value: f32,
+}
+
 impl GamepadData {
     fn new(
         id: GamepadId,
         tx: Sender<Message>,
-        gamepad: &gilrs_core::Gamepad,
+        gamepad: gilrs_core::Gamepad<'_>,
         db: &MappingDb,
     ) -> Self {
-        let mapping = db
-            .get(Uuid::from_bytes(gamepad.uuid()))
-            .and_then(|s| Mapping::parse_sdl_mapping(s, gamepad.buttons(), gamepad.axes()).ok())
-            .unwrap_or_else(|| Mapping::default(gamepad));
+        let mapping = find_mapping(gamepad, db);
 
         if gamepad.is_ff_supported() && gamepad.is_connected() {
             if let Some(device) = gamepad.ff_device() {
@@ -933,11 +1586,20 @@ impl GamepadData {
         GamepadData {
             state: GamepadState::new(),
             mapping,
+            targets: FnvHashMap::default(),
+            user_mapping: false,
             tx,
             id,
         }
     }
 
+    /// Replaces mapping and resets state, which was updated by events of previous mapping.
+    fn set_mapping(&mut self, mapping: Mapping) {
+        self.mapping = mapping;
+        self.state = GamepadState::new();
+        self.targets.clear();
+    }
+
     /// if `mapping_source()` is `SdlMappings` returns the name of the mapping used by the gamepad.
     /// Otherwise returns `None`.
     ///
@@ -960,10 +1622,15 @@ impl GamepadData {
     pub fn is_pressed(&self, btn: Button) -> bool {
         assert_ne!(btn, Button::Unknown);
 
-        self.button_code(btn)
-            .or_else(|| btn.to_nec())
-            .map(|nec| self.state.is_pressed(nec))
-            .unwrap_or(false)
+        match self.button_code(btn).or_else(|| btn.to_nec()) {
+            Some(nec) if !self.has_plain_mapping(nec) => self
+                .targets
+                .get(&(nec, AxisOrBtn::Btn(btn)))
+                .map(|target| target.pressed)
+                .unwrap_or(false),
+            Some(nec) => self.state.is_pressed(nec),
+            None => false,
+        }
     }
 
     /// Examines cached gamepad state to check axis's value. Panics if `axis` is `Unknown`.
@@ -1004,6 +1671,20 @@ impl GamepadData {
         self.mapping.map(&ec.0)
     }
 
+    /// Returns `false` if `ec` is mapped to more than one element or its value has to be
+    /// transformed (part of axis, inverted axis or output range).
+    pub(crate) fn has_plain_mapping(&self, ec: Code) -> bool {
+        match self.mapping.targets(&ec.0) {
+            [] => true,
+            [target] => {
+                target.input == AxisRange::Full
+                    && !target.inverted
+                    && target.output == AxisRange::Full
+            }
+            _ => false,
+        }
+    }
+
     /// Returns `Code` associated with `btn`.
     pub fn button_code(&self, btn: Button) -> Option<Code> {
         self.mapping.map_rev(&AxisOrBtn::Btn(btn)).map(Code)
@@ -1015,6 +1696,17 @@ impl GamepadData {
     }
 }
 
+fn find_mapping(gamepad: gilrs_core::Gamepad<'_>, db: &MappingDb) -> Mapping {
+    db.find(Uuid::from_bytes(gamepad.uuid()))
+        .and_then(|(s, rule)| {
+            let mut mapping =
+                Mapping::parse_sdl_mapping(s, gamepad.buttons(), gamepad.axes()).ok()?;
+            mapping.set_db_match(rule);
+            Some(mapping)
+        })
+        .unwrap_or_else(|| Mapping::default(gamepad))
+}
+
 /// Source of gamepad mappings.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MappingSource {
@@ -1059,16 +1751,46 @@ fn axis_value(info: &AxisInfo, val: i32, axis: Axis) -> f32 {
 
     val = val / range * 2.0 - 1.0;
 
-    if gilrs_core::IS_Y_AXIS_REVERSED
-        && (axis == Axis::LeftStickY || axis == Axis::RightStickY || axis == Axis::DPadY)
-        && val != 0.0
-    {
+    if is_y_axis_reversed(axis) && val != 0.0 {
         val = -val;
     }
 
     utils::clamp(val, -1.0, 1.0)
 }
 
+fn is_y_axis_reversed(axis: Axis) -> bool {
+    gilrs_core::IS_Y_AXIS_REVERSED
+        && (axis == Axis::LeftStickY || axis == Axis::RightStickY || axis == Axis::DPadY)
+}
+
+/// Value of element that `target` maps to, when value of mapped element is `val`.
+fn target_value(target: &Target, val: f32) -> f32 {
+    let mut val = match target.input {
+        AxisRange::Full => (val + 1.0) / 2.0,
+        AxisRange::UpperHalf => val,
+        AxisRange::LowerHalf => -val,
+    };
+    val = utils::clamp(val, 0.0, 1.0);
+
+    if target.inverted {
+        val = 1.0 - val;
+    }
+
+    match (target.to, target.output) {
+        (AxisOrBtn::Btn(_), _) => val,
+        (AxisOrBtn::Axis(axis), AxisRange::Full) => {
+            let val = val * 2.0 - 1.0;
+            if is_y_axis_reversed(axis) && val != 0.0 {
+                -val
+            } else {
+                val
+            }
+        }
+        (AxisOrBtn::Axis(_), AxisRange::UpperHalf) => val,
+        (AxisOrBtn::Axis(_), AxisRange::LowerHalf) => -val,
+    }
+}
+
 fn btn_value(info: &AxisInfo, val: i32) -> f32 {
     let range = (info.max - info.min) as f32;
     let mut val = (val - info.min) as f32;
@@ -1079,12 +1801,16 @@ fn btn_value(info: &AxisInfo, val: i32) -> f32 {
 
 /// Error type which can be returned when creating `Gilrs`.
 #[derive(Debug)]
+// `NotImplemented` is returned at most once, boxing `Gilrs` isn't worth the API change.
+#[allow(clippy::large_enum_variant)]
 pub enum Error {
     /// Gilrs does not support current platform, but you can use dummy context from this error if
     /// gamepad input is not essential.
     NotImplemented(Gilrs),
     /// Either `pressed ≤ released` or one of values is outside [0.0, 1.0] range.
     InvalidAxisToBtn,
+    /// Force feedback tick duration is shorter than 1 ms or longer than `u32::MAX` ms.
+    InvalidFfTickDuration,
     /// Platform specific error.
     Other(Box<dyn error::Error + Send + Sync + 'static>),
 }
@@ -1096,6 +1822,9 @@ impl Display for Error {
             Error::InvalidAxisToBtn => f.write_str(
                 "Either `pressed ≤ released` or one of values is outside [0.0, 1.0] range.",
             ),
+            Error::InvalidFfTickDuration => {
+                f.write_str("Force feedback tick duration is outside [1 ms, u32::MAX ms] range.")
+            }
             Error::Other(ref e) => e.fmt(f),
         }
     }
@@ -1109,3 +1838,257 @@ impl error::Error for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_gamepad::VirtualGamepad;
+    use crate::{HatDirection, MappingInput};
+
+    /// Builds virtual `Gilrs`, adds `pad` to it and consumes its `Connected` event.
+    fn connect(builder: GilrsBuilder, pad: VirtualGamepad) -> (Gilrs, Injector, GamepadId) {
+        let (mut gilrs, mut injector) = builder.build_virtual().unwrap();
+        let id = injector.add_gamepad(pad);
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Connected);
+
+        (gilrs, injector, id)
+    }
+
+    /// Returns types of all pending events.
+    fn events(gilrs: &mut Gilrs) -> Vec<EventType> {
+        std::iter::from_fn(|| gilrs.next_event())
+            .map(|ev| ev.event)
+            .collect()
+    }
+
+    #[test]
+    fn set_mapping() {
+        let south = Button::South.to_nec().unwrap();
+        let (mut gilrs, injector, id) = connect(
+            GilrsBuilder::new(),
+            VirtualGamepad::new("Pad").button(south),
+        );
+
+        let mut data = MappingData::new();
+        data.insert_btn(south, Button::East);
+        gilrs.set_mapping(id.into(), &data, "Custom").unwrap();
+        assert_eq!(gilrs.gamepad(id).name(), "Custom");
+
+        injector.press(id, south);
+        assert_eq!(
+            gilrs.next_event().unwrap().event,
+            EventType::ButtonPressed(Button::East, south)
+        );
+
+        injector.disconnect(id);
+        events(&mut gilrs);
+        assert_eq!(
+            gilrs.set_mapping(id.into(), &data, None),
+            Err(MappingError::NotConnected)
+        );
+    }
+
+    #[test]
+    fn set_mapping_ranges() {
+        let south = Button::South.to_nec().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let left_z = Axis::LeftZ.to_nec().unwrap();
+        let info = AxisInfo {
+            min: -100,
+            max: 100,
+            deadzone: Some(0),
+        };
+        let pad = VirtualGamepad::new("Pad")
+            .button(south)
+            .axis(left_x, info)
+            .axis(left_z, info);
+        let (mut gilrs, injector, id) = connect(GilrsBuilder::new(), pad);
+
+        let axis = |code, range, inverted| MappingInput::Axis {
+            code,
+            range,
+            inverted,
+        };
+        let mut data = MappingData::new();
+        data.insert_axis_input(
+            MappingInput::Button(south),
+            Axis::LeftStickX,
+            AxisRange::LowerHalf,
+        );
+        data.insert_axis_input(
+            axis(left_x, AxisRange::Full, true),
+            Axis::RightStickX,
+            AxisRange::Full,
+        );
+        data.insert_btn_input(
+            axis(left_z, AxisRange::LowerHalf, false),
+            Button::LeftTrigger2,
+        );
+        data.insert_btn_input(
+            axis(left_z, AxisRange::UpperHalf, false),
+            Button::RightTrigger2,
+        );
+        gilrs.set_mapping(id.into(), &data, None).unwrap();
+
+        injector.press(id, south);
+        injector.release(id, south);
+        injector.set_axis(id, left_x, 100);
+        injector.set_axis(id, left_z, -100);
+        injector.set_axis(id, left_z, 100);
+
+        assert_eq!(
+            events(&mut gilrs),
+            [
+                EventType::AxisChanged(Axis::LeftStickX, -1.0, south),
+                EventType::AxisChanged(Axis::LeftStickX, 0.0, south),
+                EventType::AxisChanged(Axis::RightStickX, -1.0, left_x),
+                EventType::ButtonPressed(Button::LeftTrigger2, left_z),
+                EventType::ButtonChanged(Button::LeftTrigger2, 1.0, left_z),
+                EventType::ButtonReleased(Button::LeftTrigger2, left_z),
+                EventType::ButtonChanged(Button::LeftTrigger2, 0.0, left_z),
+                EventType::ButtonPressed(Button::RightTrigger2, left_z),
+                EventType::ButtonChanged(Button::RightTrigger2, 1.0, left_z),
+            ]
+        );
+        let gamepad = gilrs.gamepad(id);
+        assert_eq!(gamepad.value(Axis::RightStickX), -1.0);
+        assert!(!gamepad.is_pressed(Button::LeftTrigger2));
+        assert!(gamepad.is_pressed(Button::RightTrigger2));
+    }
+
+    #[test]
+    fn split_axis_without_state() {
+        let left_z = Axis::LeftZ.to_nec().unwrap();
+        let info = AxisInfo {
+            min: -100,
+            max: 100,
+            deadzone: Some(0),
+        };
+        let pad = VirtualGamepad::new("Pad").axis(left_z, info);
+        let (mut gilrs, injector, id) = connect(GilrsBuilder::new().set_update_state(false), pad);
+
+        let axis = |range| MappingInput::Axis {
+            code: left_z,
+            range,
+            inverted: false,
+        };
+        let mut data = MappingData::new();
+        data.insert_btn_input(axis(AxisRange::LowerHalf), Button::LeftTrigger2);
+        data.insert_btn_input(axis(AxisRange::UpperHalf), Button::RightTrigger2);
+        gilrs.set_mapping(id.into(), &data, None).unwrap();
+
+        injector.set_axis(id, left_z, 100);
+        injector.set_axis(id, left_z, 90);
+        injector.set_axis(id, left_z, 100);
+
+        assert_eq!(
+            events(&mut gilrs),
+            [
+                EventType::ButtonPressed(Button::RightTrigger2, left_z),
+                EventType::ButtonChanged(Button::RightTrigger2, 1.0, left_z),
+                EventType::ButtonChanged(Button::RightTrigger2, 0.9, left_z),
+                EventType::ButtonChanged(Button::RightTrigger2, 1.0, left_z),
+            ]
+        );
+        let gamepad = gilrs.gamepad(id);
+        assert!(!gamepad.is_pressed(Button::LeftTrigger2));
+        assert!(gamepad.is_pressed(Button::RightTrigger2));
+    }
+
+    #[test]
+    fn set_mapping_strict() {
+        let south = Button::South.to_nec().unwrap();
+        let (mut gilrs, _injector, id) = connect(
+            GilrsBuilder::new(),
+            VirtualGamepad::new("Pad").button(south),
+        );
+
+        let mut data = MappingData::new();
+        data.insert_btn(south, Button::South);
+        assert!(gilrs.set_mapping_strict(id.into(), &data, None).is_ok());
+
+        let hat = MappingInput::Hat {
+            hat: 0,
+            direction: HatDirection::Up,
+        };
+        data.insert_btn_input(hat, Button::C);
+        assert_eq!(
+            gilrs.set_mapping_strict(id.into(), &data, None),
+            Err(MappingError::NotSdl2Compatible)
+        );
+    }
+
+    #[test]
+    fn default_mapping_string() {
+        let south = Button::South.to_nec().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let info = AxisInfo {
+            min: -128,
+            max: 127,
+            deadzone: None,
+        };
+        let pad = VirtualGamepad::new("Pad").button(south).axis(left_x, info);
+        let (gilrs, _injector, id) = connect(GilrsBuilder::new(), pad);
+
+        let sdl = gilrs.gamepad(id).mapping_string();
+        assert!(sdl.starts_with("00000000000000000000000000000000,Pad,a:b0,leftx:a0,platform:"));
+    }
+
+    #[test]
+    fn mappings_reload_interval() {
+        let (gilrs, _injector) = GilrsBuilder::new().build_virtual().unwrap();
+        assert_eq!(gilrs.mappings_reload_interval, None);
+
+        let path = std::env::temp_dir().join("gilrs-interval.txt");
+        let (gilrs, _injector) = GilrsBuilder::new()
+            .add_mappings_path(path)
+            .build_virtual()
+            .unwrap();
+        assert_eq!(gilrs.mappings_reload_interval, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn reload_mappings() {
+        let path = std::env::temp_dir().join(format!("gilrs-reload-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let builder = GilrsBuilder::new()
+            .add_mappings_path(&path)
+            .set_mappings_reload_interval(None);
+        let south = Button::South.to_nec().unwrap();
+        let uuid = [3, 0, 0, 0, 0x26, 9, 0, 0, 0x88, 0x88, 0, 0, 0, 1, 0, 1];
+        let pad = VirtualGamepad::new("Pad").uuid(uuid).button(south);
+        let (mut gilrs, injector, id) = connect(builder, pad);
+        assert_eq!(gilrs.gamepad(id).name(), "Pad");
+        assert!(!gilrs.reload_mappings());
+
+        injector.press(id, south);
+        events(&mut gilrs);
+        assert!(gilrs.gamepad(id).is_pressed(Button::South));
+
+        let line = "03000000260900008888000000010001,Mapped pad,a:b0,";
+        std::fs::write(&path, line).unwrap();
+        assert!(gilrs.reload_mappings());
+        assert_eq!(gilrs.gamepad(id).name(), "Mapped pad");
+        assert_eq!(gilrs.gamepad(id).mapping_match(), Some(MappingMatch::Exact));
+        assert!(!gilrs.gamepad(id).is_pressed(Button::South));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(gilrs.reload_mappings());
+        assert_eq!(gilrs.gamepad(id).name(), "Pad");
+        assert_eq!(gilrs.gamepad(id).mapping_match(), None);
+
+        let mut data = MappingData::new();
+        data.insert_btn(south, Button::East);
+        gilrs.set_mapping(id.into(), &data, "Custom").unwrap();
+        std::fs::write(&path, line).unwrap();
+        assert!(!gilrs.reload_mappings());
+        assert_eq!(gilrs.gamepad(id).name(), "Custom");
+
+        // Mapping set by user is dropped when gamepad is reconnected.
+        injector.disconnect(id);
+        injector.connect(id);
+        events(&mut gilrs);
+        assert_eq!(gilrs.gamepad(id).name(), "Mapped pad");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -84,6 +84,9 @@
 //!
 //! - `serde-serialize` - enable deriving of serde's `Serialize` and `Deserialize` for
 //!   various types.
+//! - `async` - implement `futures::Stream` for `Gilrs`. On Linux task is woken when gamepads' or
+//!   udev monitor's file descriptors become readable, on other platforms backend is polled in
+//!   short intervals.
 //!
 //! Platform specific notes
 //! ======================
@@ -112,10 +115,16 @@ extern crate log;
 mod constants;
 mod gamepad;
 mod mapping;
+#[cfg(feature = "async")]
+mod stream;
 mod utils;
 
 pub mod ev;
 pub mod ff;
+pub mod lint;
+pub mod record;
+pub mod virtual_gamepad;
+pub mod wizard;
 
 pub use crate::ev::filter::Filter;
 pub use crate::ev::{Axis, Button, Event, EventType};
@@ -123,4 +132,6 @@ pub use crate::gamepad::{
     ConnectedGamepadsIterator, Error, Gamepad, GamepadId, Gilrs, GilrsBuilder, MappingSource,
     PowerInfo,
 };
-pub use crate::mapping::{MappingData as Mapping, MappingError};
+pub use crate::mapping::{
+    AxisRange, HatDirection, MappingData as Mapping, MappingError, MappingInput, MappingMatch,
+};
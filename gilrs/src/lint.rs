@@ -0,0 +1,325 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Checking mapping files.
+//!
+//! [`GilrsBuilder::add_mappings()`](../struct.GilrsBuilder.html#method.add_mappings) and mapping
+//! files silently skip lines that can't be used. [`check_mappings()`](fn.check_mappings.html)
+//! instead reports every problem it finds, with line and column, so custom mappings can be
+//! validated before they are shipped (for example in CI).
+//!
+//! Reported problems are invalid lines, unknown fields, duplicated GUIDs and mappings for other
+//! platforms. Empty lines and lines starting with `#` are ignored.
+//!
+//! # Example
+//!
+//! ```
+//! use gilrs::lint::{self, DiagnosticKind};
+//!
+//! let mappings = "03000000de280000ff11000001000000,Steam Virtual Gamepad,a:b0,b:b1,\
+//!                 leftx:a0,lefty:a1,rightstik:b9,platform:Linux,";
+//!
+//! for diagnostic in lint::check_mappings(mappings) {
+//!     assert_eq!(diagnostic.kind, DiagnosticKind::UnknownField("rightstik".to_owned()));
+//!     assert_eq!(diagnostic.to_string(), "1:84: unknown field `rightstik`");
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+pub use crate::mapping::parser::ErrorKind;
+use crate::mapping::parser::{Parser, Token};
+use crate::mapping::SDL_PLATFORM_NAME;
+
+/// Platforms that can appear in `platform` field of SDL mapping.
+const SDL_PLATFORMS: [&str; 5] = ["Windows", "Mac OS X", "Linux", "iOS", "Android"];
+
+/// Problem found in a mapping file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Column (in characters) where the problem starts, starting from 1.
+    pub column: usize,
+    /// What is wrong.
+    pub kind: DiagnosticKind,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+/// Kind of problem found in a mapping file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// Mapping could not be parsed. If this error is `EmptyValue`, only the field at diagnostic's
+    /// position is skipped and the rest of the mapping is used. Otherwise whole mapping is
+    /// rejected and gamepad uses default mapping.
+    ParseError(ErrorKind),
+    /// Field is neither button, axis nor other field known to SDL. Whole mapping is rejected and
+    /// gamepad uses default mapping. Name doesn't contain `+` or `-` prefix of half of axis.
+    UnknownField(String),
+    /// Mapping for the same GUID, platform and hints was already defined at line `first_line`.
+    /// Only the last definition is used.
+    DuplicateGuid { first_line: usize },
+    /// Value of `platform` field is not a platform supported by SDL. Mapping is never used.
+    UnknownPlatform(String),
+    /// Mapping is for a different platform than the current one and will be ignored here.
+    OtherPlatform(String),
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::ParseError(kind) => Display::fmt(kind, f),
+            DiagnosticKind::UnknownField(name) => write!(f, "unknown field `{}`", name),
+            DiagnosticKind::DuplicateGuid { first_line } => write!(
+                f,
+                "mapping for this GUID was already defined at line {}",
+                first_line
+            ),
+            DiagnosticKind::UnknownPlatform(name) => write!(f, "unknown platform `{}`", name),
+            DiagnosticKind::OtherPlatform(name) => write!(
+                f,
+                "mapping is for {}, it will be ignored on {}",
+                name, SDL_PLATFORM_NAME
+            ),
+        }
+    }
+}
+
+/// Checks mappings in SDL_GameControllerDB format and returns all problems found, ordered by
+/// line and column.
+pub fn check_mappings(mappings: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    // (GUID, platform, hints) -> line of first definition
+    let mut defined = HashMap::new();
+
+    for (idx, line) in mappings.lines().enumerate() {
+        let line_nr = idx + 1;
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let first_diagnostic = diagnostics.len();
+        let mut push = |pos: usize, kind| {
+            diagnostics.push(Diagnostic {
+                line: line_nr,
+                column: line[..pos].chars().count() + 1,
+                kind,
+            })
+        };
+
+        let mut parser = Parser::new(line);
+        let mut uuid = None;
+        let mut platform = "";
+        let mut hints = Vec::new();
+
+        while let Some(token) = parser.next_token() {
+            match token {
+                Ok(Token::Uuid(u)) => uuid = Some(u),
+                Ok(Token::Platform(name)) => {
+                    platform = name;
+                    let pos = parser.token_position();
+                    if !SDL_PLATFORMS.contains(&name) {
+                        push(pos, DiagnosticKind::UnknownPlatform(name.to_owned()));
+                    } else if name != SDL_PLATFORM_NAME {
+                        push(pos, DiagnosticKind::OtherPlatform(name.to_owned()));
+                    }
+                }
+                Ok(Token::Hint {
+                    name,
+                    negated,
+                    default,
+                }) => hints.push((name, negated, default)),
+                Ok(_) => (),
+                Err(err) => {
+                    let kind = *err.kind();
+                    let pos = err.position();
+                    match kind {
+                        ErrorKind::UnknownButton | ErrorKind::UnknownAxis => {
+                            let field = line[pos..].split(&[':', ','][..]).next();
+                            let field = field
+                                .unwrap_or_default()
+                                .trim_start_matches(&['+', '-'][..])
+                                .to_owned();
+                            push(pos, DiagnosticKind::UnknownField(field));
+                        }
+                        _ => push(pos, DiagnosticKind::ParseError(kind)),
+                    }
+
+                    // Parser can't recover from these errors.
+                    if kind == ErrorKind::InvalidGuid || kind == ErrorKind::UnexpectedEnd {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(uuid) = uuid {
+            hints.sort_unstable();
+            match defined.get(&(uuid, platform, hints.clone())) {
+                Some(&first_line) => push(0, DiagnosticKind::DuplicateGuid { first_line }),
+                None => {
+                    defined.insert((uuid, platform, hints), line_nr);
+                }
+            }
+        }
+
+        diagnostics[first_diagnostic..].sort_by_key(|d| d.column);
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ev::{AxisOrBtn, Button};
+    use crate::mapping::{Mapping, MappingDb};
+    use gilrs_core::native_ev_codes as nec;
+    use uuid::Uuid;
+
+    #[test]
+    fn check() {
+        let other = if SDL_PLATFORM_NAME == "Windows" {
+            "Linux"
+        } else {
+            "Windows"
+        };
+        let mappings = format!(
+            "# Comment\n\
+             \n\
+             03000000de280000ff11000001000000,Pad,a:b0,b:b1,platform:{0},\n\
+             03000000de280000ff11000001000000,Pad,a:b0,foo:b1,x:,platform:{0},\n\
+             03000000de280000ff11000001000000,Pad,a:b0,hint:!SDL_HINT:=1,platform:{0},\n\
+             030000xx,Pad,a:b0,\n\
+             03000000de280000ff11000002000000,Pad,a:b0,platform:{1},\n\
+             03000000de280000ff11000003000000,Pad,a:b0,platform:Amiga,\n",
+            SDL_PLATFORM_NAME, other
+        );
+
+        let diagnostics = check_mappings(&mappings);
+        let expected = vec![
+            Diagnostic {
+                line: 4,
+                column: 1,
+                kind: DiagnosticKind::DuplicateGuid { first_line: 3 },
+            },
+            Diagnostic {
+                line: 4,
+                column: 43,
+                kind: DiagnosticKind::UnknownField("foo".to_owned()),
+            },
+            Diagnostic {
+                line: 4,
+                column: 50,
+                kind: DiagnosticKind::ParseError(ErrorKind::EmptyValue),
+            },
+            Diagnostic {
+                line: 6,
+                column: 1,
+                kind: DiagnosticKind::ParseError(ErrorKind::InvalidGuid),
+            },
+            Diagnostic {
+                line: 7,
+                column: 43,
+                kind: DiagnosticKind::OtherPlatform(other.to_owned()),
+            },
+            Diagnostic {
+                line: 8,
+                column: 43,
+                kind: DiagnosticKind::UnknownPlatform("Amiga".to_owned()),
+            },
+        ];
+
+        assert_eq!(diagnostics, expected);
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "4:43: unknown field `foo`".to_owned()
+        );
+    }
+
+    #[test]
+    fn diagnostics_match_mapping_db() {
+        let other = if SDL_PLATFORM_NAME == "Windows" {
+            "Linux"
+        } else {
+            "Windows"
+        };
+        let buttons = [nec::BTN_SOUTH, nec::BTN_EAST];
+        let guid = "03000000de280000ff11000001000000";
+        // Checks that `line` produces only diagnostic `kind` and returns what `MappingDb` and
+        // `Mapping` do with it.
+        let check = |line: String, kind| {
+            let kinds = check_mappings(&line)
+                .into_iter()
+                .map(|d| d.kind)
+                .collect::<Vec<_>>();
+            assert_eq!(kinds, [kind], "{}", line);
+
+            let mut db = MappingDb::new();
+            db.insert(&line);
+            db.get(Uuid::parse_str(guid).unwrap())
+                .map(|s| Mapping::parse_sdl_mapping(s, &buttons, &[]).ok())
+        };
+
+        // Only the empty field is skipped.
+        let mapping = check(
+            format!("{},Pad,a:b0,x:,b:b1,platform:{},", guid, SDL_PLATFORM_NAME),
+            DiagnosticKind::ParseError(ErrorKind::EmptyValue),
+        );
+        let mapping = mapping.unwrap().unwrap();
+        assert_eq!(
+            mapping.map(&nec::BTN_EAST),
+            Some(AxisOrBtn::Btn(Button::East))
+        );
+
+        // Whole mapping is rejected.
+        let mapping = check(
+            format!("{},Pad,a:b0,b:bx,platform:{},", guid, SDL_PLATFORM_NAME),
+            DiagnosticKind::ParseError(ErrorKind::InvalidValue),
+        );
+        assert_eq!(mapping, Some(None));
+        let mapping = check(
+            format!("{},Pad,a:b0,+foo:b1,platform:{},", guid, SDL_PLATFORM_NAME),
+            DiagnosticKind::UnknownField("foo".to_owned()),
+        );
+        assert_eq!(mapping, Some(None));
+
+        // Only the last definition is used.
+        let mapping = check(
+            format!(
+                "{0},Pad,a:b0,platform:{1},\n{0},Pad,a:b1,platform:{1},",
+                guid, SDL_PLATFORM_NAME
+            ),
+            DiagnosticKind::DuplicateGuid { first_line: 1 },
+        );
+        let mapping = mapping.unwrap().unwrap();
+        assert_eq!(
+            mapping.map(&nec::BTN_EAST),
+            Some(AxisOrBtn::Btn(Button::South))
+        );
+
+        // Mapping is never used.
+        let mapping = check(
+            format!("{},Pad,a:b0,platform:Amiga,", guid),
+            DiagnosticKind::UnknownPlatform("Amiga".to_owned()),
+        );
+        assert_eq!(mapping, None);
+        let mapping = check(
+            format!("{},Pad,a:b0,platform:{},", guid, other),
+            DiagnosticKind::OtherPlatform(other.to_owned()),
+        );
+        assert_eq!(mapping, None);
+    }
+}
@@ -6,7 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 #![cfg_attr(target_os = "windows", allow(dead_code))]
 
-mod parser;
+pub(crate) mod parser;
 
 use crate::ev::{self, Axis, AxisOrBtn, Button};
 use gilrs_core::native_ev_codes as nec;
@@ -15,7 +15,10 @@ use gilrs_core::EvCode;
 use std::collections::HashMap;
 use std::env;
 use std::error::Error;
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use fnv::FnvHashMap;
 use uuid::Uuid;
@@ -25,17 +28,17 @@ use self::parser::{Error as ParserError, ErrorKind as ParserErrorKind, Parser, T
 
 /// Platform name used by SDL mappings
 #[cfg(target_os = "linux")]
-const SDL_PLATFORM_NAME: &str = "Linux";
+pub(crate) const SDL_PLATFORM_NAME: &str = "Linux";
 #[cfg(target_os = "macos")]
-const SDL_PLATFORM_NAME: &'static str = "Mac OS X";
+pub(crate) const SDL_PLATFORM_NAME: &'static str = "Mac OS X";
 #[cfg(target_os = "windows")]
-const SDL_PLATFORM_NAME: &'static str = "Windows";
+pub(crate) const SDL_PLATFORM_NAME: &'static str = "Windows";
 #[cfg(all(
     not(target_os = "linux"),
     not(target_os = "macos"),
     not(target_os = "windows")
 ))]
-const SDL_PLATFORM_NAME: &'static str = "Unknown";
+pub(crate) const SDL_PLATFORM_NAME: &'static str = "Unknown";
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -43,23 +46,109 @@ const SDL_PLATFORM_NAME: &'static str = "Unknown";
 ///
 /// This struct is internal, `MappingData` is exported in public interface as `Mapping`.
 pub struct Mapping {
-    mappings: FnvHashMap<EvCode, AxisOrBtn>,
+    mappings: FnvHashMap<EvCode, Vec<Target>>,
+    /// Mappings as they would be written in SDL format, used by `to_sdl_string()`.
+    bindings: Vec<SdlBinding>,
     name: String,
     default: bool,
     hats_mapped: u8,
+    /// Rule that was used to find this mapping in `MappingDb`.
+    db_match: Option<MappingMatch>,
+}
+
+/// Element that button or axis of gamepad is mapped to and how its value is transformed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Target {
+    pub to: AxisOrBtn,
+    /// Part of gamepad's axis that is mapped. Always `Full` for buttons.
+    pub input: AxisRange,
+    pub inverted: bool,
+    /// Part of axis that input is mapped to. Always `Full` for buttons.
+    pub output: AxisRange,
+}
+
+impl Target {
+    fn new(to: AxisOrBtn) -> Self {
+        Target {
+            to,
+            input: AxisRange::Full,
+            inverted: false,
+            output: AxisRange::Full,
+        }
+    }
+}
+
+/// Single `key:value` pair of SDL mapping.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct SdlBinding {
+    input: MappingInput,
+    to: AxisOrBtn,
+    output: AxisRange,
+}
+
+impl SdlBinding {
+    fn new(input: MappingInput, to: AxisOrBtn) -> Self {
+        SdlBinding {
+            input,
+            to,
+            output: AxisRange::Full,
+        }
+    }
+
+    /// Writes binding in SDL format. Does nothing if it can't be represented with given buttons
+    /// and axes.
+    fn write_sdl(&self, out: &mut String, buttons: &[EvCode], axes: &[EvCode]) {
+        let name = match parser::sdl_name(self.to) {
+            Some(name) => name,
+            None => return,
+        };
+
+        let value = match self.input {
+            MappingInput::Button(code) => match buttons.iter().position(|&x| x == code.0) {
+                Some(idx) => format!("b{}", idx),
+                None => return,
+            },
+            MappingInput::Axis {
+                code,
+                range,
+                inverted,
+            } => match axes.iter().position(|&x| x == code.0) {
+                Some(idx) => format!(
+                    "{}a{}{}",
+                    range_prefix(range),
+                    idx,
+                    if inverted { "~" } else { "" }
+                ),
+                None => return,
+            },
+            MappingInput::Hat { hat, direction } => format!("h{}.{}", hat, direction as u16),
+        };
+
+        let _ = write!(out, "{}{}:{},", range_prefix(self.output), name, value);
+    }
+}
+
+fn range_prefix(range: AxisRange) -> &'static str {
+    match range {
+        AxisRange::LowerHalf => "-",
+        AxisRange::UpperHalf => "+",
+        AxisRange::Full => "",
+    }
 }
 
 impl Mapping {
     pub fn new() -> Self {
         Mapping {
             mappings: FnvHashMap::default(),
+            bindings: Vec::new(),
             name: String::new(),
             default: false,
             hats_mapped: 0,
+            db_match: None,
         }
     }
 
-    pub fn default(gamepad: &gilrs_core::Gamepad) -> Self {
+    pub fn default(gamepad: gilrs_core::Gamepad<'_>) -> Self {
         use self::Axis as Ax;
         use self::AxisOrBtn::*;
 
@@ -161,11 +250,43 @@ impl Mapping {
             }
         }
 
+        let mut bindings = Vec::new();
+        for (&code, &to) in &mappings {
+            if gamepad.buttons().contains(&code) {
+                bindings.push(SdlBinding::new(MappingInput::Button(ev::Code(code)), to));
+            } else if to == Axis(Ax::DPadX) || to == Axis(Ax::DPadY) {
+                // Dpad axes are hat in SDL.
+                let dirs = if to == Axis(Ax::DPadX) {
+                    [
+                        (Button::DPadRight, HatDirection::Right),
+                        (Button::DPadLeft, HatDirection::Left),
+                    ]
+                } else {
+                    [
+                        (Button::DPadUp, HatDirection::Up),
+                        (Button::DPadDown, HatDirection::Down),
+                    ]
+                };
+                for &(btn, direction) in &dirs {
+                    let input = MappingInput::Hat { hat: 0, direction };
+                    bindings.push(SdlBinding::new(input, Btn(btn)));
+                }
+            } else {
+                bindings.push(SdlBinding::new(MappingInput::axis(ev::Code(code)), to));
+            }
+        }
+        bindings.sort_by_key(|binding| parser::sdl_name(binding.to));
+
         Mapping {
-            mappings,
+            mappings: mappings
+                .into_iter()
+                .map(|(code, to)| (code, vec![Target::new(to)]))
+                .collect(),
+            bindings,
             name: String::new(),
             default: true,
             hats_mapped: 0,
+            db_match: None,
         }
     }
 
@@ -186,80 +307,67 @@ impl Mapping {
             return Err(MappingError::InvalidName);
         }
 
-        let mut mappings = FnvHashMap::default();
-        let mut sdl_mappings = format!("{},{},", uuid.to_simple(), name);
+        let mut mapping = Mapping {
+            mappings: FnvHashMap::default(),
+            bindings: Vec::new(),
+            name: name.to_owned(),
+            default: false,
+            hats_mapped: 0,
+            db_match: None,
+        };
 
-        {
-            let mut add_button = |ident, ev_code, mapped_btn| {
-                Self::add_button(
-                    ident,
-                    ev_code,
-                    mapped_btn,
-                    buttons,
-                    &mut sdl_mappings,
-                    &mut mappings,
-                )
+        for (button, &input) in &data.buttons {
+            let to = match button as u16 {
+                BTN_SOUTH => Button::South,
+                BTN_EAST => Button::East,
+                BTN_WEST => Button::West,
+                BTN_NORTH => Button::North,
+                BTN_LT => Button::LeftTrigger,
+                BTN_RT => Button::RightTrigger,
+                BTN_LT2 => Button::LeftTrigger2,
+                BTN_RT2 => Button::RightTrigger2,
+                BTN_SELECT => Button::Select,
+                BTN_START => Button::Start,
+                BTN_MODE => Button::Mode,
+                BTN_LTHUMB => Button::LeftThumb,
+                BTN_RTHUMB => Button::RightThumb,
+                BTN_DPAD_UP => Button::DPadUp,
+                BTN_DPAD_DOWN => Button::DPadDown,
+                BTN_DPAD_LEFT => Button::DPadLeft,
+                BTN_DPAD_RIGHT => Button::DPadRight,
+                BTN_C => Button::C,
+                BTN_Z => Button::Z,
+                BTN_PADDLE1 => Button::Paddle1,
+                BTN_PADDLE2 => Button::Paddle2,
+                BTN_PADDLE3 => Button::Paddle3,
+                BTN_PADDLE4 => Button::Paddle4,
+                BTN_MISC1 => Button::Misc1,
+                BTN_TOUCHPAD => Button::Touchpad,
+                BTN_UNKNOWN => return Err(MappingError::UnknownElement),
+                _ => unreachable!(),
             };
 
-            for (button, &ev_code) in &data.buttons {
-                match button as u16 {
-                    BTN_SOUTH => add_button("a", ev_code, Button::South)?,
-                    BTN_EAST => add_button("b", ev_code, Button::East)?,
-                    BTN_WEST => add_button("x", ev_code, Button::West)?,
-                    BTN_NORTH => add_button("y", ev_code, Button::North)?,
-                    BTN_LT => add_button("leftshoulder", ev_code, Button::LeftTrigger)?,
-                    BTN_RT => add_button("rightshoulder", ev_code, Button::RightTrigger)?,
-                    BTN_LT2 => add_button("lefttrigger", ev_code, Button::LeftTrigger2)?,
-                    BTN_RT2 => add_button("righttrigger", ev_code, Button::RightTrigger2)?,
-                    BTN_SELECT => add_button("back", ev_code, Button::Select)?,
-                    BTN_START => add_button("start", ev_code, Button::Start)?,
-                    BTN_MODE => add_button("guide", ev_code, Button::Mode)?,
-                    BTN_LTHUMB => add_button("leftstick", ev_code, Button::LeftThumb)?,
-                    BTN_RTHUMB => add_button("rightstick", ev_code, Button::RightThumb)?,
-                    BTN_DPAD_UP => add_button("dpup", ev_code, Button::DPadUp)?,
-                    BTN_DPAD_DOWN => add_button("dpdown", ev_code, Button::DPadDown)?,
-                    BTN_DPAD_LEFT => add_button("dpleft", ev_code, Button::DPadLeft)?,
-                    BTN_DPAD_RIGHT => add_button("dpright", ev_code, Button::DPadRight)?,
-                    BTN_C => add_button("c", ev_code, Button::C)?,
-                    BTN_Z => add_button("z", ev_code, Button::Z)?,
-                    BTN_UNKNOWN => return Err(MappingError::UnknownElement),
-                    _ => unreachable!(),
-                }
-            }
+            mapping.add_input(input, AxisOrBtn::Btn(to), AxisRange::Full, buttons, axes)?;
         }
 
-        {
-            let mut add_axis = |ident, ev_code, mapped_axis| {
-                Self::add_axis(
-                    ident,
-                    ev_code,
-                    mapped_axis,
-                    axes,
-                    &mut sdl_mappings,
-                    &mut mappings,
-                )
-            };
-
-            for (axis, &ev_code) in &data.axes {
-                match axis as u16 {
-                    AXIS_LSTICKX => add_axis("leftx", ev_code, Axis::LeftStickX)?,
-                    AXIS_LSTICKY => add_axis("lefty", ev_code, Axis::LeftStickY)?,
-                    AXIS_RSTICKX => add_axis("rightx", ev_code, Axis::RightStickX)?,
-                    AXIS_RSTICKY => add_axis("righty", ev_code, Axis::RightStickY)?,
-                    AXIS_LEFTZ => add_axis("leftz", ev_code, Axis::LeftZ)?,
-                    AXIS_RIGHTZ => add_axis("rightz", ev_code, Axis::RightZ)?,
+        for &range in &[AxisRange::Full, AxisRange::LowerHalf, AxisRange::UpperHalf] {
+            for (axis, &input) in &data.axes[range as usize] {
+                let to = match axis as u16 {
+                    AXIS_LSTICKX => Axis::LeftStickX,
+                    AXIS_LSTICKY => Axis::LeftStickY,
+                    AXIS_RSTICKX => Axis::RightStickX,
+                    AXIS_RSTICKY => Axis::RightStickY,
+                    AXIS_LEFTZ => Axis::LeftZ,
+                    AXIS_RIGHTZ => Axis::RightZ,
                     AXIS_UNKNOWN => return Err(MappingError::UnknownElement),
                     _ => unreachable!(),
-                }
+                };
+
+                mapping.add_input(input, AxisOrBtn::Axis(to), range, buttons, axes)?;
             }
         }
 
-        let mapping = Mapping {
-            mappings,
-            name: name.to_owned(),
-            default: false,
-            hats_mapped: 0,
-        };
+        let sdl_mappings = mapping.to_sdl_string(uuid, name, buttons, axes);
 
         Ok((mapping, sdl_mappings))
     }
@@ -287,54 +395,75 @@ impl Mapping {
                         warn!("Mappings for different platform – {}", platform);
                     }
                 }
-                Token::Uuid(_) => (),
+                // Hints are checked when mapping is added to `MappingDb`.
+                Token::Uuid(_) | Token::Hint { .. } | Token::Sdk => (),
                 Token::Name(name) => mapping.name = name.to_owned(),
-                Token::AxisMapping { from, to, .. } => {
+                Token::AxisMapping {
+                    from,
+                    to,
+                    input,
+                    output,
+                    inverted,
+                } => {
                     let axis = axes
                         .get(from as usize)
                         .cloned()
                         .ok_or(ParseSdlMappingError::InvalidAxis)?;
-                    mapping.mappings.insert(axis, to);
+                    mapping.insert_target(
+                        axis,
+                        Target {
+                            to,
+                            input,
+                            inverted,
+                            output,
+                        },
+                    );
+                    mapping.bindings.push(SdlBinding {
+                        input: MappingInput::Axis {
+                            code: ev::Code(axis),
+                            range: input,
+                            inverted,
+                        },
+                        to,
+                        output,
+                    });
                 }
-                Token::ButtonMapping { from, to } => {
+                Token::ButtonMapping { from, to, output } => {
                     let btn = buttons
                         .get(from as usize)
                         .cloned()
                         .ok_or(ParseSdlMappingError::InvalidButton)?;
-                    mapping.mappings.insert(btn, AxisOrBtn::Btn(to));
+                    mapping.insert_target(
+                        btn,
+                        Target {
+                            output,
+                            ..Target::new(to)
+                        },
+                    );
+                    mapping.bindings.push(SdlBinding {
+                        input: MappingInput::Button(ev::Code(btn)),
+                        to,
+                        output,
+                    });
                 }
                 Token::HatMapping { hat, direction, to } => {
-                    if hat != 0 || !to.is_dpad() {
-                        warn!(
-                            "Hat mappings are only supported for dpads (requested to map hat \
-                             {}.{} to {:?}",
-                            hat, direction, to
-                        );
-                    } else {
-                        // We  don't have anything like "hat" in gilrs, so let's jus assume that
-                        // user want to map dpad axes.
-                        //
-                        // We have to add mappings for axes AND buttons, because axis_dpad_to_button
-                        // filter may transform event to button event.
-                        let (from_axis, from_btn) = match direction {
-                            1 => (nec::AXIS_DPADY, nec::BTN_DPAD_UP),
-                            4 => (nec::AXIS_DPADY, nec::BTN_DPAD_DOWN),
-                            2 => (nec::AXIS_DPADX, nec::BTN_DPAD_RIGHT),
-                            8 => (nec::AXIS_DPADX, nec::BTN_DPAD_LEFT),
-                            0 => continue, // FIXME: I have no idea what 0 means here
-                            _ => return Err(ParseSdlMappingError::UnknownHatDirection),
-                        };
-
-                        let to_axis = match to {
-                            Button::DPadLeft | Button::DPadRight => Axis::DPadX,
-                            Button::DPadUp | Button::DPadDown => Axis::DPadY,
-                            _ => unreachable!(),
-                        };
-
-                        mapping.mappings.insert(from_axis, AxisOrBtn::Axis(to_axis));
-                        mapping.mappings.insert(from_btn, AxisOrBtn::Btn(to));
-                        mapping.hats_mapped |= direction as u8;
-                    }
+                    let direction = match HatDirection::from_sdl(direction) {
+                        Some(direction) => direction,
+                        // FIXME: I have no idea what 0 means here
+                        None if direction == 0 => continue,
+                        None if hat == 0 && to.is_dpad() => {
+                            return Err(ParseSdlMappingError::UnknownHatDirection)
+                        }
+                        None => {
+                            warn!("Unknown direction of hat {}.{}", hat, direction);
+                            continue;
+                        }
+                    };
+                    let input = MappingInput::Hat { hat, direction };
+                    let to = AxisOrBtn::Btn(to);
+
+                    mapping.map_hat(hat, direction, to);
+                    mapping.bindings.push(SdlBinding::new(input, to));
                 }
             }
         }
@@ -342,50 +471,137 @@ impl Mapping {
         Ok(mapping)
     }
 
-    fn add_button(
-        ident: &str,
-        ev_code: EvCode,
-        mapped_btn: Button,
+    fn add_input(
+        &mut self,
+        input: MappingInput,
+        to: AxisOrBtn,
+        output: AxisRange,
         buttons: &[EvCode],
-        sdl_mappings: &mut String,
-        mappings: &mut FnvHashMap<EvCode, AxisOrBtn>,
+        axes: &[EvCode],
     ) -> Result<(), MappingError> {
-        let n_btn = buttons
-            .iter()
-            .position(|&x| x == ev_code)
-            .ok_or(MappingError::InvalidCode(ev::Code(ev_code)))?;
-        sdl_mappings.push_str(&format!("{}:b{},", ident, n_btn));
-        mappings.insert(ev_code, AxisOrBtn::Btn(mapped_btn));
+        match input {
+            MappingInput::Button(code) => {
+                if !buttons.contains(&code.0) {
+                    return Err(MappingError::InvalidCode(code));
+                }
+                self.insert_target(
+                    code.0,
+                    Target {
+                        output,
+                        ..Target::new(to)
+                    },
+                );
+            }
+            MappingInput::Axis {
+                code,
+                range,
+                inverted,
+            } => {
+                if !axes.contains(&code.0) {
+                    return Err(MappingError::InvalidCode(code));
+                }
+                let target = Target {
+                    to,
+                    input: range,
+                    inverted,
+                    output,
+                };
+                self.insert_target(code.0, target);
+            }
+            MappingInput::Hat { hat, direction } => self.map_hat(hat, direction, to),
+        }
+
+        self.bindings.push(SdlBinding { input, to, output });
+
         Ok(())
     }
 
-    fn add_axis(
-        ident: &str,
-        ev_code: EvCode,
-        mapped_axis: Axis,
+    /// Adds `target` to targets of `code`. Only halves of axis can be mapped to different
+    /// elements, in other cases previous targets are replaced.
+    fn insert_target(&mut self, code: EvCode, target: Target) {
+        let targets = self.mappings.entry(code).or_default();
+        if target.input == AxisRange::Full || targets.iter().any(|t| t.input == AxisRange::Full) {
+            targets.clear();
+        }
+        targets.retain(|t| t.input != target.input);
+        targets.push(target);
+    }
+
+    fn map_hat(&mut self, hat: u16, direction: HatDirection, to: AxisOrBtn) {
+        let to = match to {
+            AxisOrBtn::Btn(btn) if hat == 0 && btn.is_dpad() => btn,
+            _ => {
+                warn!(
+                    "Hat mappings are only supported for dpads (requested to map hat {}.{} to \
+                     {:?}",
+                    hat, direction as u16, to
+                );
+                return;
+            }
+        };
+
+        // We  don't have anything like "hat" in gilrs, so let's jus assume that user want to map
+        // dpad axes.
+        //
+        // We have to add mappings for axes AND buttons, because axis_dpad_to_button filter may
+        // transform event to button event.
+        let (from_axis, from_btn) = match direction {
+            HatDirection::Up => (nec::AXIS_DPADY, nec::BTN_DPAD_UP),
+            HatDirection::Down => (nec::AXIS_DPADY, nec::BTN_DPAD_DOWN),
+            HatDirection::Right => (nec::AXIS_DPADX, nec::BTN_DPAD_RIGHT),
+            HatDirection::Left => (nec::AXIS_DPADX, nec::BTN_DPAD_LEFT),
+        };
+
+        let to_axis = match to {
+            Button::DPadLeft | Button::DPadRight => Axis::DPadX,
+            Button::DPadUp | Button::DPadDown => Axis::DPadY,
+            _ => unreachable!(),
+        };
+
+        self.insert_target(from_axis, Target::new(AxisOrBtn::Axis(to_axis)));
+        self.insert_target(from_btn, Target::new(AxisOrBtn::Btn(to)));
+        self.hats_mapped |= direction as u8;
+    }
+
+    /// Returns mapping in SDL format, as used in SDL_GameControllerDB. Elements that can't be
+    /// represented in SDL mapping, or that gamepad with `buttons` and `axes` doesn't have, are
+    /// skipped. Commas in `name` are replaced by spaces.
+    pub fn to_sdl_string(
+        &self,
+        uuid: Uuid,
+        name: &str,
+        buttons: &[EvCode],
         axes: &[EvCode],
-        sdl_mappings: &mut String,
-        mappings: &mut FnvHashMap<EvCode, AxisOrBtn>,
-    ) -> Result<(), MappingError> {
-        let n_axis = axes
-            .iter()
-            .position(|&x| x == ev_code)
-            .ok_or(MappingError::InvalidCode(ev::Code(ev_code)))?;
-        sdl_mappings.push_str(&format!("{}:a{},", ident, n_axis));
-        mappings.insert(ev_code, AxisOrBtn::Axis(mapped_axis));
-        Ok(())
+    ) -> String {
+        let mut sdl = format!("{},{},", uuid.to_simple(), name.replace(',', " "));
+        for binding in &self.bindings {
+            binding.write_sdl(&mut sdl, buttons, axes);
+        }
+        let _ = write!(sdl, "platform:{},", SDL_PLATFORM_NAME);
+
+        sdl
     }
 
     fn is_name_valid(name: &str) -> bool {
         !name.chars().any(|x| x == ',')
     }
 
+    /// Returns element that `code` is mapped to. If halves of axis are mapped to different
+    /// elements, the first one is returned.
     pub fn map(&self, code: &EvCode) -> Option<AxisOrBtn> {
-        self.mappings.get(code).cloned()
+        self.targets(code).first().map(|target| target.to)
+    }
+
+    /// Returns all elements that `code` is mapped to.
+    pub fn targets(&self, code: &EvCode) -> &[Target] {
+        self.mappings.get(code).map(Vec::as_slice).unwrap_or(&[])
     }
 
     pub fn map_rev(&self, el: &AxisOrBtn) -> Option<EvCode> {
-        self.mappings.iter().find(|x| x.1 == el).map(|x| *x.0)
+        self.mappings
+            .iter()
+            .find(|(_, targets)| targets.iter().any(|target| target.to == *el))
+            .map(|(&code, _)| code)
     }
 
     pub fn is_default(&self) -> bool {
@@ -397,6 +613,14 @@ impl Mapping {
     pub fn hats_mapped(&self) -> u8 {
         self.hats_mapped
     }
+
+    pub fn db_match(&self) -> Option<MappingMatch> {
+        self.db_match
+    }
+
+    pub fn set_db_match(&mut self, db_match: MappingMatch) {
+        self.db_match = Some(db_match);
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -439,12 +663,21 @@ impl Display for ParseSdlMappingError {
 #[derive(Debug)]
 pub struct MappingDb {
     mappings: HashMap<Uuid, String>,
+    /// Files and directories added with `add_path()`.
+    paths: Vec<PathBuf>,
+    /// Files that `file_mappings` were loaded from, with their modification time and size.
+    files: Vec<(PathBuf, Option<SystemTime>, u64)>,
+    /// Mappings loaded from `paths`. They take precedence over `mappings`.
+    file_mappings: HashMap<Uuid, String>,
 }
 
 impl MappingDb {
     pub fn new() -> Self {
         MappingDb {
             mappings: HashMap::new(),
+            paths: Vec::new(),
+            files: Vec::new(),
+            file_mappings: HashMap::new(),
         }
     }
 
@@ -461,32 +694,207 @@ impl MappingDb {
     }
 
     pub fn insert(&mut self, s: &str) {
-        for mapping in s.lines() {
-            let pat = "platform:";
-            if let Some(offset) = mapping.find(pat).map(|o| o + pat.len()) {
-                let s = &mapping[offset..];
-                let end = s.find(',').unwrap_or_else(|| s.len());
+        insert_mappings(&mut self.mappings, s);
+    }
 
-                if &s[..end] != SDL_PLATFORM_NAME {
-                    continue;
-                }
+    /// Adds file or directory with mappings. Directories are not searched recursively, only
+    /// `.txt` files directly inside them are used. Path doesn't have to exist yet. Mappings are
+    /// loaded by `reload()`.
+    pub fn add_path(&mut self, path: PathBuf) {
+        self.paths.push(path);
+    }
+
+    /// Returns `true` if any path was added with `add_path()`.
+    pub fn has_paths(&self) -> bool {
+        !self.paths.is_empty()
+    }
+
+    /// Reloads mappings from paths added with `add_path()` if any file was added, removed or
+    /// modified since last call. Returns UUIDs of gamepads whose mappings have changed.
+    pub fn reload(&mut self) -> Vec<Uuid> {
+        let files = self.scan_paths();
+        if files == self.files {
+            return Vec::new();
+        }
+
+        let mut file_mappings = HashMap::new();
+        for (path, _, _) in &files {
+            match fs::read_to_string(path) {
+                Ok(s) => insert_mappings(&mut file_mappings, &s),
+                Err(e) => warn!("Failed to read mappings from {}: {}", path.display(), e),
             }
+        }
 
-            mapping
-                .split(',')
-                .next()
-                .and_then(|s| Uuid::parse_str(s).ok())
-                .and_then(|uuid| self.mappings.insert(uuid, mapping.to_owned()));
+        let mut changed = file_mappings
+            .iter()
+            .filter(|&(uuid, mapping)| self.file_mappings.get(uuid) != Some(mapping))
+            .map(|(&uuid, _)| uuid)
+            .collect::<Vec<_>>();
+        changed.extend(
+            self.file_mappings
+                .keys()
+                .filter(|uuid| !file_mappings.contains_key(uuid)),
+        );
+
+        self.files = files;
+        self.file_mappings = file_mappings;
+
+        changed
+    }
+
+    /// Returns files from `paths` that currently exist, in the order in which they are loaded.
+    fn scan_paths(&self) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+        let mut files = Vec::new();
+
+        for path in &self.paths {
+            if path.is_dir() {
+                let mut entries = match fs::read_dir(path) {
+                    Ok(entries) => entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| is_mapping_file(path))
+                        .collect::<Vec<_>>(),
+                    Err(e) => {
+                        warn!("Failed to read directory {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                entries.sort();
+                files.extend(entries);
+            } else if path.is_file() {
+                files.push(path.clone());
+            }
         }
+
+        files
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                Some((path, metadata.modified().ok(), metadata.len()))
+            })
+            .collect()
+    }
+
+    /// Finds mapping for gamepad with given UUID. If there is no mapping for exactly the same UUID,
+    /// mapping for the same device (bus, vendor and product) with different version is used and
+    /// then one that also has different CRC of name.
+    pub fn find(&self, uuid: Uuid) -> Option<(&str, MappingMatch)> {
+        if let Some(mapping) = self.get(uuid) {
+            return Some((mapping, MappingMatch::Exact));
+        }
+
+        if !is_vid_pid_guid(uuid) {
+            return None;
+        }
+
+        for &rule in &[MappingMatch::IgnoringVersion, MappingMatch::IgnoringCrc] {
+            let key = rule.normalize(uuid);
+            for &mappings in &[&self.file_mappings, &self.mappings] {
+                // Several mappings can match, pick one in the same way every time.
+                let found = mappings
+                    .iter()
+                    .filter(|&(&other, _)| is_vid_pid_guid(other) && rule.normalize(other) == key)
+                    .min_by_key(|&(&other, _)| other);
+
+                if let Some((_, mapping)) = found {
+                    return Some((mapping, rule));
+                }
+            }
+        }
+
+        None
     }
 
     pub fn get(&self, uuid: Uuid) -> Option<&str> {
-        self.mappings.get(&uuid).map(String::as_ref)
+        self.file_mappings
+            .get(&uuid)
+            .or_else(|| self.mappings.get(&uuid))
+            .map(String::as_ref)
     }
 
     pub fn len(&self) -> usize {
         self.mappings.len()
+            + self
+                .file_mappings
+                .keys()
+                .filter(|uuid| !self.mappings.contains_key(uuid))
+                .count()
+    }
+}
+
+fn insert_mappings(mappings: &mut HashMap<Uuid, String>, s: &str) {
+    for mapping in s.lines() {
+        let pat = "platform:";
+        if let Some(offset) = mapping.find(pat).map(|o| o + pat.len()) {
+            let s = &mapping[offset..];
+            let end = s.find(',').unwrap_or_else(|| s.len());
+
+            if &s[..end] != SDL_PLATFORM_NAME {
+                continue;
+            }
+        }
+
+        if mapping.contains("hint:") && !hints_match(mapping) {
+            continue;
+        }
+
+        mapping
+            .split(',')
+            .next()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .and_then(|uuid| mappings.insert(uuid, mapping.to_owned()));
+    }
+}
+
+/// Returns false if mapping has `hint:` field that doesn't match behaviour of gilrs.
+fn hints_match(mapping: &str) -> bool {
+    let mut parser = Parser::new(mapping);
+
+    while let Some(token) = parser.next_token() {
+        if let Ok(Token::Hint {
+            name,
+            negated,
+            default,
+        }) = token
+        {
+            let value = match name {
+                // `Button::South` is always the bottom button, whatever its label is.
+                "SDL_GAMECONTROLLER_USE_BUTTON_LABELS" => false,
+                _ => default,
+            };
+
+            if value == negated {
+                return false;
+            }
+        }
     }
+
+    true
+}
+
+/// Returns true if mapping for `other` UUID can be used for gamepad with `uuid` by
+/// `MappingDb::find()`.
+pub fn can_match(uuid: Uuid, other: Uuid) -> bool {
+    uuid == other
+        || (is_vid_pid_guid(uuid)
+            && is_vid_pid_guid(other)
+            && MappingMatch::IgnoringCrc.normalize(uuid)
+                == MappingMatch::IgnoringCrc.normalize(other))
+}
+
+/// Returns true if UUID is in SDL format that contains bus, vendor and product ID.
+fn is_vid_pid_guid(uuid: Uuid) -> bool {
+    let bytes = uuid.as_bytes();
+
+    bytes[6] == 0 && bytes[7] == 0 && bytes[10] == 0 && bytes[11] == 0
+}
+
+fn is_mapping_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("txt"))
+            .unwrap_or(false)
 }
 
 /// Stores data used to map gamepad buttons and axes.
@@ -499,8 +907,9 @@ impl MappingDb {
 #[derive(Debug, Clone, Default)]
 // Re-exported as Mapping
 pub struct MappingData {
-    buttons: VecMap<EvCode>,
-    axes: VecMap<EvCode>,
+    buttons: VecMap<MappingInput>,
+    /// Axes mapped to each `AxisRange`, indexed by `AxisRange as usize`.
+    axes: [VecMap<MappingInput>; 3],
 }
 
 impl MappingData {
@@ -508,38 +917,232 @@ impl MappingData {
     pub fn new() -> Self {
         MappingData {
             buttons: VecMap::with_capacity(18),
-            axes: VecMap::with_capacity(11),
+            axes: [VecMap::new(), VecMap::new(), VecMap::with_capacity(11)],
         }
     }
 
-    /// Returns `EvCode` associated with button index.
+    /// Returns `EvCode` associated with button index. If button is mapped from hat, `None` is
+    /// returned, use [`button_input()`](#method.button_input) instead.
     pub fn button(&self, idx: Button) -> Option<ev::Code> {
-        self.buttons.get(idx as usize).cloned().map(ev::Code)
+        self.button_input(idx).and_then(MappingInput::code)
     }
 
-    /// Returns `EvCode` associated with axis index.
+    /// Returns `EvCode` associated with axis index. Only mappings to whole axis are considered,
+    /// use [`axis_input()`](#method.axis_input) for halves of axis.
     pub fn axis(&self, idx: Axis) -> Option<ev::Code> {
-        self.axes.get(idx as usize).cloned().map(ev::Code)
+        self.axis_input(idx, AxisRange::Full)
+            .and_then(MappingInput::code)
+    }
+
+    /// Returns element of gamepad mapped to button.
+    pub fn button_input(&self, idx: Button) -> Option<MappingInput> {
+        self.buttons.get(idx as usize).cloned()
+    }
+
+    /// Returns element of gamepad mapped to `range` of axis.
+    pub fn axis_input(&self, idx: Axis, range: AxisRange) -> Option<MappingInput> {
+        self.axes[range as usize].get(idx as usize).cloned()
     }
 
     /// Inserts new button mapping.
     pub fn insert_btn(&mut self, from: ev::Code, to: Button) -> Option<ev::Code> {
-        self.buttons.insert(to as usize, from.0).map(ev::Code)
+        self.insert_btn_input(MappingInput::Button(from), to)
+            .and_then(MappingInput::code)
     }
 
     /// Inserts new axis mapping.
     pub fn insert_axis(&mut self, from: ev::Code, to: Axis) -> Option<ev::Code> {
-        self.axes.insert(to as usize, from.0).map(ev::Code)
+        self.insert_axis_input(MappingInput::axis(from), to, AxisRange::Full)
+            .and_then(MappingInput::code)
+    }
+
+    /// Maps button, half of axis or hat direction to button and returns previous mapping.
+    ///
+    /// ```
+    /// use gilrs::{AxisRange, Button, Mapping, MappingInput};
+    /// # let code = gilrs::Axis::LeftZ.to_nec().unwrap();
+    ///
+    /// // Trigger is lower half of axis.
+    /// let mut data = Mapping::new();
+    /// data.insert_btn_input(
+    ///     MappingInput::Axis {
+    ///         code,
+    ///         range: AxisRange::LowerHalf,
+    ///         inverted: false,
+    ///     },
+    ///     Button::LeftTrigger2,
+    /// );
+    /// ```
+    pub fn insert_btn_input(&mut self, from: MappingInput, to: Button) -> Option<MappingInput> {
+        self.buttons.insert(to as usize, from)
+    }
+
+    /// Maps axis or button to `range` of axis and returns previous mapping. Use
+    /// `AxisRange::LowerHalf` or `AxisRange::UpperHalf` to map button to one direction of axis.
+    pub fn insert_axis_input(
+        &mut self,
+        from: MappingInput,
+        to: Axis,
+        range: AxisRange,
+    ) -> Option<MappingInput> {
+        self.axes[range as usize].insert(to as usize, from)
     }
 
     /// Removes button and returns associated `NativEvCode`.
     pub fn remove_button(&mut self, idx: Button) -> Option<ev::Code> {
-        self.buttons.remove(idx as usize).map(ev::Code)
+        self.buttons
+            .remove(idx as usize)
+            .and_then(MappingInput::code)
     }
 
-    /// Removes axis and returns associated `NativEvCode`.
+    /// Removes axis and returns associated `NativEvCode`. Mappings to halves of axis are not
+    /// removed.
     pub fn remove_axis(&mut self, idx: Axis) -> Option<ev::Code> {
-        self.axes.remove(idx as usize).map(ev::Code)
+        self.remove_axis_input(idx, AxisRange::Full)
+            .and_then(MappingInput::code)
+    }
+
+    /// Removes mapping to `range` of axis and returns it.
+    pub fn remove_axis_input(&mut self, idx: Axis, range: AxisRange) -> Option<MappingInput> {
+        self.axes[range as usize].remove(idx as usize)
+    }
+
+    /// Returns mapping in SDL format, as used in SDL_GameControllerDB, without applying it to any
+    /// gamepad. `buttons` and `axes` are codes of all gamepad's buttons and axes in the same order
+    /// as reported by gamepad (for example
+    /// [`RecordedGamepad::buttons`](record/struct.RecordedGamepad.html#structfield.buttons)); SDL refers
+    /// to them by their index.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as
+    /// [`Gilrs::set_mapping()`](struct.Gilrs.html#method.set_mapping), except `NotConnected`.
+    pub fn to_sdl_string(
+        &self,
+        uuid: [u8; 16],
+        name: &str,
+        buttons: &[ev::Code],
+        axes: &[ev::Code],
+    ) -> Result<String, MappingError> {
+        let buttons = buttons.iter().map(|code| code.0).collect::<Vec<_>>();
+        let axes = axes.iter().map(|code| code.0).collect::<Vec<_>>();
+
+        Mapping::from_data(self, &buttons, &axes, name, Uuid::from_bytes(uuid)).map(|(_, s)| s)
+    }
+
+    /// Returns true if any range of axis is mapped.
+    pub(crate) fn has_axis(&self, idx: Axis) -> bool {
+        self.axes.iter().any(|axes| axes.contains_key(idx as usize))
+    }
+}
+
+/// Rule that was used to find SDL mapping for gamepad. See
+/// [`Gamepad::mapping_match()`](struct.Gamepad.html#method.mapping_match).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MappingMatch {
+    /// Mapping has the same UUID as gamepad.
+    Exact,
+    /// Mapping is for the same bus, vendor and product, but different version of device.
+    IgnoringVersion,
+    /// Mapping is for the same bus, vendor and product, but different version of device and
+    /// different CRC of its name (used by newer SDL versions).
+    IgnoringCrc,
+}
+
+impl MappingMatch {
+    /// Clears parts of SDL GUID that are ignored by this rule.
+    fn normalize(self, uuid: Uuid) -> Uuid {
+        let mut bytes = *uuid.as_bytes();
+
+        match self {
+            MappingMatch::Exact => (),
+            MappingMatch::IgnoringVersion => {
+                bytes[12] = 0;
+                bytes[13] = 0;
+            }
+            MappingMatch::IgnoringCrc => {
+                bytes[2] = 0;
+                bytes[3] = 0;
+                bytes[12] = 0;
+                bytes[13] = 0;
+            }
+        }
+
+        Uuid::from_bytes(bytes)
+    }
+}
+
+/// Part of axis used in mapping.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AxisRange {
+    /// Values from minimum to center.
+    LowerHalf,
+    /// Values from center to maximum.
+    UpperHalf,
+    /// All values.
+    Full,
+}
+
+/// Direction of hat switch. Values are the same as in SDL mappings.
+#[repr(u16)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HatDirection {
+    Up = 1,
+    Right = 2,
+    Down = 4,
+    Left = 8,
+}
+
+impl HatDirection {
+    fn from_sdl(direction: u16) -> Option<Self> {
+        match direction {
+            1 => Some(HatDirection::Up),
+            2 => Some(HatDirection::Right),
+            4 => Some(HatDirection::Down),
+            8 => Some(HatDirection::Left),
+            _ => None,
+        }
+    }
+}
+
+/// Element of gamepad that is mapped to button or axis.
+///
+/// Ranges and inversion are applied to values of events. Halves of the same axis can be mapped to
+/// different elements.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MappingInput {
+    /// Button.
+    Button(ev::Code),
+    /// Axis or half of axis, optionally inverted.
+    Axis {
+        code: ev::Code,
+        range: AxisRange,
+        inverted: bool,
+    },
+    /// Direction of hat switch. Only hat 0 mapped to dpad buttons is supported by gilrs, other
+    /// hats are only preserved in SDL mappings.
+    Hat { hat: u16, direction: HatDirection },
+}
+
+impl MappingInput {
+    /// Whole, not inverted axis.
+    pub fn axis(code: ev::Code) -> Self {
+        MappingInput::Axis {
+            code,
+            range: AxisRange::Full,
+            inverted: false,
+        }
+    }
+
+    /// Returns `Code` of button or axis, or `None` for hat.
+    pub fn code(self) -> Option<ev::Code> {
+        match self {
+            MappingInput::Button(code) | MappingInput::Axis { code, .. } => Some(code),
+            MappingInput::Hat { .. } => None,
+        }
     }
 }
 
@@ -685,6 +1288,207 @@ mod tests {
         assert_eq!(Err(MappingError::UnknownElement), incorrect_mappings);
     }
 
+    #[test]
+    fn from_data_ranges() {
+        let uuid = Uuid::nil();
+        let code = |c| ev::Code(c);
+
+        let mut data = MappingData::new();
+        data.insert_btn_input(
+            MappingInput::Axis {
+                code: code(AXES[9]),
+                range: AxisRange::UpperHalf,
+                inverted: false,
+            },
+            Button::LeftTrigger2,
+        );
+        data.insert_btn_input(
+            MappingInput::Hat {
+                hat: 0,
+                direction: HatDirection::Left,
+            },
+            Button::DPadLeft,
+        );
+        data.insert_axis_input(
+            MappingInput::Axis {
+                code: code(AXES[1]),
+                range: AxisRange::Full,
+                inverted: true,
+            },
+            Axis::LeftStickY,
+            AxisRange::Full,
+        );
+        data.insert_axis_input(
+            MappingInput::Button(code(BUTTONS[3])),
+            Axis::RightStickX,
+            AxisRange::LowerHalf,
+        );
+        data.insert_axis_input(
+            MappingInput::Button(code(BUTTONS[4])),
+            Axis::RightStickX,
+            AxisRange::UpperHalf,
+        );
+
+        assert_eq!(data.axis(Axis::LeftStickY), Some(code(AXES[1])));
+        assert_eq!(data.axis(Axis::RightStickX), None);
+        assert_eq!(data.button(Button::DPadLeft), None);
+
+        let (mapping, sdl) = Mapping::from_data(&data, &BUTTONS, &AXES, "Pad", uuid).unwrap();
+        assert_eq!(
+            sdl,
+            format!(
+                "00000000000000000000000000000000,Pad,lefttrigger:+a9,dpleft:h0.8,lefty:a1~,\
+                 -rightx:b3,+rightx:b4,platform:{},",
+                SDL_PLATFORM_NAME
+            )
+        );
+        assert_eq!(mapping.hats_mapped(), HatDirection::Left as u8);
+        assert_eq!(
+            mapping,
+            Mapping::parse_sdl_mapping(&sdl, &BUTTONS, &AXES).unwrap()
+        );
+
+        data.insert_axis_input(
+            MappingInput::axis(code(BUTTONS[0])),
+            Axis::RightStickY,
+            AxisRange::Full,
+        );
+        assert_eq!(
+            Err(MappingError::InvalidCode(code(BUTTONS[0]))),
+            Mapping::from_data(&data, &BUTTONS, &AXES, "Pad", uuid)
+        );
+    }
+
+    #[test]
+    fn to_sdl_string() {
+        let line = format!(
+            "03000000260900008888000000010001,GameCube,a:b0,b:b2,dpup:h0.1,dpleft:h0.8,leftx:a0,\
+             lefty:a1~,-rightx:-a2,+rightx:+a3,lefttrigger:+a4,platform:{},",
+            SDL_PLATFORM_NAME
+        );
+        let uuid = Uuid::parse_str("03000000260900008888000000010001").unwrap();
+        let mapping = Mapping::parse_sdl_mapping(&line, &BUTTONS, &AXES).unwrap();
+
+        assert_eq!(
+            mapping.to_sdl_string(uuid, "GameCube", &BUTTONS, &AXES),
+            line
+        );
+        assert_eq!(
+            mapping.to_sdl_string(uuid, "Game,Cube", &BUTTONS[..1], &AXES[..1]),
+            format!(
+                "03000000260900008888000000010001,Game Cube,a:b0,dpup:h0.1,dpleft:h0.8,\
+                 leftx:a0,platform:{},",
+                SDL_PLATFORM_NAME
+            )
+        );
+    }
+
+    #[test]
+    fn mapping_data_to_sdl_string() {
+        let buttons = BUTTONS.iter().map(|&nec| ev::Code(nec)).collect::<Vec<_>>();
+        let axes = AXES.iter().map(|&nec| ev::Code(nec)).collect::<Vec<_>>();
+        let uuid = *Uuid::parse_str("03000000260900008888000000010001")
+            .unwrap()
+            .as_bytes();
+
+        let mut data = MappingData::new();
+        data.insert_btn(buttons[1], Button::South);
+        data.insert_axis(axes[3], Axis::LeftStickX);
+
+        assert_eq!(
+            data.to_sdl_string(uuid, "Pad", &buttons, &axes),
+            Ok(format!(
+                "03000000260900008888000000010001,Pad,a:b1,leftx:a3,platform:{},",
+                SDL_PLATFORM_NAME
+            ))
+        );
+        assert_eq!(
+            data.to_sdl_string(uuid, "P,ad", &buttons, &axes),
+            Err(MappingError::InvalidName)
+        );
+        assert_eq!(
+            data.to_sdl_string(uuid, "Pad", &buttons, &axes[..1]),
+            Err(MappingError::InvalidCode(axes[3]))
+        );
+    }
+
+    #[test]
+    fn new_sdl_fields() {
+        let line = "03000000260900008888000000010001,Elite,a:b0,paddle1:b1,paddle4:b2,misc1:b3,\
+                    touchpad:b4,sdk>=:29,hint:!SDL_GAMECONTROLLER_USE_BUTTON_LABELS:=1,";
+        let mapping = Mapping::parse_sdl_mapping(line, &BUTTONS, &AXES).unwrap();
+
+        assert_eq!(
+            mapping.map(&BUTTONS[0]),
+            Some(AxisOrBtn::Btn(Button::South))
+        );
+        assert_eq!(
+            mapping.map(&BUTTONS[1]),
+            Some(AxisOrBtn::Btn(Button::Paddle1))
+        );
+        assert_eq!(
+            mapping.map(&BUTTONS[2]),
+            Some(AxisOrBtn::Btn(Button::Paddle4))
+        );
+        assert_eq!(
+            mapping.map(&BUTTONS[3]),
+            Some(AxisOrBtn::Btn(Button::Misc1))
+        );
+        assert_eq!(
+            mapping.map(&BUTTONS[4]),
+            Some(AxisOrBtn::Btn(Button::Touchpad))
+        );
+
+        let invalid = "03000000260900008888000000010001,Elite,a:b0,sdk<=:x,";
+        assert!(Mapping::parse_sdl_mapping(invalid, &BUTTONS, &AXES).is_err());
+    }
+
+    #[test]
+    fn hints() {
+        let uuid = Uuid::parse_str("03000000260900008888000000010001").unwrap();
+        let positional = "03000000260900008888000000010001,Pad,a:b0,b:b1,\
+                          hint:!SDL_GAMECONTROLLER_USE_BUTTON_LABELS:=1,";
+        let labels = "03000000260900008888000000010001,Pad,a:b1,b:b0,\
+                      hint:SDL_GAMECONTROLLER_USE_BUTTON_LABELS:=1,";
+
+        let mut db = MappingDb::new();
+        db.insert(&format!("{}\n{}", positional, labels));
+        assert_eq!(db.get(uuid), Some(positional));
+
+        let mut db = MappingDb::new();
+        db.insert("03000000260900008888000000010001,Pad,a:b0,hint:SDL_UNKNOWN_HINT,");
+        assert_eq!(db.get(uuid), None);
+        db.insert("03000000260900008888000000010001,Pad,a:b0,hint:SDL_UNKNOWN_HINT:=1,");
+        assert!(db.get(uuid).is_some());
+    }
+
+    #[test]
+    fn fallback_lookup() {
+        let uuid = |s| Uuid::parse_str(s).unwrap();
+        let mut db = MappingDb::new();
+        db.insert(
+            "030000005e0400008e02000014010000,Version,a:b0,\n\
+             0300abcd5e040000ea02000000000000,Crc,a:b0,\n\
+             05000000506164000000000000000000,Name based,a:b0,",
+        );
+
+        let found = |s| db.find(uuid(s)).map(|(m, rule)| (&m[33..], rule));
+        assert_eq!(
+            found("030000005e0400008e02000014010000"),
+            Some(("Version,a:b0,", MappingMatch::Exact))
+        );
+        assert_eq!(
+            found("030000005e0400008e02000010010000"),
+            Some(("Version,a:b0,", MappingMatch::IgnoringVersion))
+        );
+        assert_eq!(
+            found("030000005e040000ea02000008050000"),
+            Some(("Crc,a:b0,", MappingMatch::IgnoringCrc))
+        );
+        assert_eq!(found("050000005e0400008e02000014010000"), None);
+        assert_eq!(found("05000000506164000000000000000001"), None);
+    }
+
     #[test]
     fn with_mappings() {
         let mappings = format!(
@@ -700,4 +1504,34 @@ mod tests {
             db.get(Uuid::parse_str("03000000260900008888000000010001").unwrap())
         );
     }
+
+    #[test]
+    fn reload_files() {
+        let dir = env::temp_dir().join(format!("gilrs-mappings-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let uuid = Uuid::parse_str("03000000260900008888000000010001").unwrap();
+
+        let mut db = MappingDb::new();
+        db.add_path(dir.clone());
+        assert!(db.reload().is_empty());
+        assert_eq!(None, db.get(uuid));
+
+        fs::write(dir.join("ignored.cfg"), TEST_STR).unwrap();
+        fs::write(dir.join("pads.txt"), TEST_STR).unwrap();
+        assert_eq!(vec![uuid], db.reload());
+        assert_eq!(Some(TEST_STR), db.get(uuid));
+        assert!(db.reload().is_empty());
+
+        let renamed = TEST_STR.replace("GameCube", "Renamed GameCube");
+        fs::write(dir.join("pads.txt"), &renamed).unwrap();
+        assert_eq!(vec![uuid], db.reload());
+        assert_eq!(Some(renamed.as_str()), db.get(uuid));
+
+        fs::remove_file(dir.join("pads.txt")).unwrap();
+        assert_eq!(vec![uuid], db.reload());
+        assert_eq!(None, db.get(uuid));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -9,10 +9,11 @@ use std::fmt::{self, Display};
 
 use uuid::Uuid;
 
+use super::AxisRange;
 use crate::ev::{Axis, AxisOrBtn, Button};
 
 // Must be sorted!
-static BUTTONS_SDL: [&str; 19] = [
+static BUTTONS_SDL: [&str; 25] = [
     "a",
     "b",
     "back",
@@ -25,15 +26,21 @@ static BUTTONS_SDL: [&str; 19] = [
     "leftshoulder",
     "leftstick",
     "lefttrigger",
+    "misc1",
+    "paddle1",
+    "paddle2",
+    "paddle3",
+    "paddle4",
     "rightshoulder",
     "rightstick",
     "righttrigger",
     "start",
+    "touchpad",
     "x",
     "y",
     "z",
 ];
-static BUTTONS: [Button; 19] = [
+static BUTTONS: [Button; 25] = [
     Button::South,
     Button::East,
     Button::Select,
@@ -46,17 +53,23 @@ static BUTTONS: [Button; 19] = [
     Button::LeftTrigger,
     Button::LeftThumb,
     Button::LeftTrigger2,
+    Button::Misc1,
+    Button::Paddle1,
+    Button::Paddle2,
+    Button::Paddle3,
+    Button::Paddle4,
     Button::RightTrigger,
     Button::RightThumb,
     Button::RightTrigger2,
     Button::Start,
+    Button::Touchpad,
     Button::West,
     Button::North,
     Button::Z,
 ];
 
 // Must be sorted!
-static AXES_SDL: [&str; 25] = [
+static AXES_SDL: [&str; 31] = [
     "a",
     "b",
     "back",
@@ -72,6 +85,11 @@ static AXES_SDL: [&str; 25] = [
     "leftx",
     "lefty",
     "leftz",
+    "misc1",
+    "paddle1",
+    "paddle2",
+    "paddle3",
+    "paddle4",
     "rightshoulder",
     "rightstick",
     "righttrigger",
@@ -79,11 +97,12 @@ static AXES_SDL: [&str; 25] = [
     "righty",
     "rightz",
     "start",
+    "touchpad",
     "x",
     "y",
     "z",
 ];
-static AXES: [AxisOrBtn; 25] = [
+static AXES: [AxisOrBtn; 31] = [
     AxisOrBtn::Btn(Button::South),
     AxisOrBtn::Btn(Button::East),
     AxisOrBtn::Btn(Button::Select),
@@ -99,6 +118,11 @@ static AXES: [AxisOrBtn; 25] = [
     AxisOrBtn::Axis(Axis::LeftStickX),
     AxisOrBtn::Axis(Axis::LeftStickY),
     AxisOrBtn::Axis(Axis::LeftZ),
+    AxisOrBtn::Btn(Button::Misc1),
+    AxisOrBtn::Btn(Button::Paddle1),
+    AxisOrBtn::Btn(Button::Paddle2),
+    AxisOrBtn::Btn(Button::Paddle3),
+    AxisOrBtn::Btn(Button::Paddle4),
     AxisOrBtn::Btn(Button::RightTrigger),
     AxisOrBtn::Btn(Button::RightThumb),
     AxisOrBtn::Btn(Button::RightTrigger2),
@@ -106,14 +130,21 @@ static AXES: [AxisOrBtn; 25] = [
     AxisOrBtn::Axis(Axis::RightStickY),
     AxisOrBtn::Axis(Axis::RightZ),
     AxisOrBtn::Btn(Button::Start),
+    AxisOrBtn::Btn(Button::Touchpad),
     AxisOrBtn::Btn(Button::West),
     AxisOrBtn::Btn(Button::North),
     AxisOrBtn::Btn(Button::Z),
 ];
 
+/// Returns SDL name of the element or `None` if it can't be used in SDL mapping.
+pub fn sdl_name(el: AxisOrBtn) -> Option<&'static str> {
+    AXES.iter().position(|&x| x == el).map(|idx| AXES_SDL[idx])
+}
+
 pub struct Parser<'a> {
     data: &'a str,
     pos: usize,
+    token_pos: usize,
     state: State,
 }
 
@@ -122,14 +153,16 @@ impl<'a> Parser<'a> {
         Parser {
             data: mapping,
             pos: 0,
+            token_pos: 0,
             state: State::Uuid,
         }
     }
 
-    pub fn next_token(&mut self) -> Option<Result<Token<'_>, Error>> {
+    pub fn next_token(&mut self) -> Option<Result<Token<'a>, Error>> {
         if self.pos >= self.data.len() {
             None
         } else {
+            self.token_pos = self.pos;
             Some(match self.state {
                 State::Uuid => self.parse_uuid(),
                 State::Name => self.parse_name(),
@@ -139,7 +172,12 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_uuid(&mut self) -> Result<Token<'_>, Error> {
+    /// Byte offset of the field that produced last token.
+    pub fn token_position(&self) -> usize {
+        self.token_pos
+    }
+
+    fn parse_uuid(&mut self) -> Result<Token<'a>, Error> {
         let next_comma = self.next_comma_or_end();
         let uuid = Uuid::parse_str(&self.data[self.pos..next_comma])
             .map(Token::Uuid)
@@ -159,7 +197,7 @@ impl<'a> Parser<'a> {
         uuid
     }
 
-    fn parse_name(&mut self) -> Result<Token<'_>, Error> {
+    fn parse_name(&mut self) -> Result<Token<'a>, Error> {
         let next_comma = self.next_comma_or_end();
         let name = &self.data[self.pos..next_comma];
 
@@ -169,12 +207,18 @@ impl<'a> Parser<'a> {
         Ok(Token::Name(name))
     }
 
-    fn parse_key_val(&mut self) -> Result<Token<'_>, Error> {
+    fn parse_key_val(&mut self) -> Result<Token<'a>, Error> {
         let next_comma = self.next_comma_or_end();
         let pair = &self.data[self.pos..next_comma];
         let pos = self.pos;
         self.pos = next_comma + 1;
 
+        // Value of hint contains colons, so it has to be handled before splitting.
+        let mut hint = pair.splitn(2, ':');
+        if let (Some("hint"), Some(value)) = (hint.next(), hint.next()) {
+            return Self::parse_hint(value, pos + 5);
+        }
+
         let mut split = pair.split(':');
         let key = split
             .next()
@@ -195,6 +239,13 @@ impl<'a> Parser<'a> {
             return Ok(Token::Platform(value));
         }
 
+        if key == "sdk>=" || key == "sdk<=" {
+            return value
+                .parse::<u32>()
+                .map(|_| Token::Sdk)
+                .map_err(|_| Error::new(ErrorKind::InvalidValue, pos + key.len() + 1));
+        }
+
         let mut input = AxisRange::Full;
         let mut output = AxisRange::Full;
         let mut inverted = false;
@@ -264,21 +315,21 @@ impl<'a> Parser<'a> {
         .parse::<u16>()
         .or_else(|_| Err(Error::new(ErrorKind::InvalidValue, pos)))?;
 
-        if is_axis {
-            let key = match key.get(0..1) {
-                Some("+") => {
-                    output = AxisRange::UpperHalf;
+        let key = match key.get(0..1) {
+            Some("+") => {
+                output = AxisRange::UpperHalf;
 
-                    &key[1..]
-                }
-                Some("-") => {
-                    output = AxisRange::LowerHalf;
+                &key[1..]
+            }
+            Some("-") => {
+                output = AxisRange::LowerHalf;
 
-                    &key[1..]
-                }
-                _ => key,
-            };
+                &key[1..]
+            }
+            _ => key,
+        };
 
+        if is_axis {
             let idx = AXES_SDL
                 .binary_search(&key)
                 .or_else(|_| Err(Error::new(ErrorKind::UnknownAxis, pos)))?;
@@ -291,17 +342,43 @@ impl<'a> Parser<'a> {
                 inverted,
             })
         } else {
-            let idx = BUTTONS_SDL
+            // Buttons can be mapped to halves of axes, for example `-leftx:b1`.
+            let idx = AXES_SDL
                 .binary_search(&key)
                 .or_else(|_| Err(Error::new(ErrorKind::UnknownButton, pos)))?;
 
             Ok(Token::ButtonMapping {
                 from,
-                to: BUTTONS[idx],
+                to: AXES[idx],
+                output,
             })
         }
     }
 
+    /// Parses value of `hint:` field, which has format `[!]NAME[:=DEFAULT]`.
+    fn parse_hint(hint: &'a str, pos: usize) -> Result<Token<'a>, Error> {
+        let negated = hint.starts_with('!');
+        let hint = hint.trim_start_matches('!');
+
+        let (name, default) = match hint.find(":=") {
+            Some(idx) => {
+                let default = &hint[(idx + 2)..];
+                (&hint[..idx], default != "0" && default != "false")
+            }
+            None => (hint, false),
+        };
+
+        if name.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidValue, pos));
+        }
+
+        Ok(Token::Hint {
+            name,
+            negated,
+            default,
+        })
+    }
+
     fn next_comma_or_end(&self) -> usize {
         self.data[self.pos..]
             .find(',')
@@ -323,8 +400,19 @@ pub enum Token<'a> {
     },
     ButtonMapping {
         from: u16,
-        to: Button,
+        to: AxisOrBtn,
+        output: AxisRange,
     },
+    /// Mapping should only be used if value of SDL hint `name` (or `default` if hint is not set),
+    /// negated if `negated` is true, is true.
+    Hint {
+        name: &'a str,
+        negated: bool,
+        default: bool,
+    },
+    /// `sdk>=` or `sdk<=` field. Android SDK version is not checked, mappings for Android are
+    /// skipped because of their platform anyway.
+    Sdk,
     // This is just SDL representation, we will convert this to axis mapping later
     HatMapping {
         hat: u16,
@@ -334,13 +422,6 @@ pub enum Token<'a> {
     },
 }
 
-#[repr(u8)]
-pub enum AxisRange {
-    LowerHalf,
-    UpperHalf,
-    Full,
-}
-
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum State {
     Uuid,
@@ -363,9 +444,16 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    /// Byte offset in parsed mapping where error was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Reason why SDL mapping could not be parsed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ErrorKind {
     InvalidGuid,
     InvalidKeyValPair,
@@ -379,9 +467,9 @@ pub enum ErrorKind {
 
 impl StdError for Error {}
 
-impl Display for Error {
+impl Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self.kind {
+        let s = match self {
             ErrorKind::InvalidGuid => "GUID is invalid",
             ErrorKind::InvalidKeyValPair => "expected key value pair",
             ErrorKind::InvalidValue => "value is not valid",
@@ -392,6 +480,12 @@ impl Display for Error {
             ErrorKind::UnexpectedEnd => "mapping does not have all required fields",
         };
 
-        f.write_fmt(format_args!("{} at {}", s, self.position))
+        f.write_str(s)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("{} at {}", self.kind, self.position))
     }
 }
@@ -0,0 +1,336 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Recording and replaying events.
+//!
+//! [`Recorder`](struct.Recorder.html) captures events returned by `Gilrs::next_event()` together
+//! with information about gamepads that emitted them (name, UUID, buttons and axes). Resulting
+//! [`Recording`](struct.Recording.html) can be replayed with
+//! [`GilrsBuilder::build_replay()`](../struct.GilrsBuilder.html#method.build_replay) on any
+//! machine, without the original devices.
+//!
+//! If `serde-serialize` feature is enabled, `Recording` implements `Serialize` and `Deserialize`,
+//! so it can be saved to a file in any format supported by serde. Note that `Code`s are platform
+//! specific, so recording should be replayed on the same platform it was created on.
+//!
+//! # Example
+//!
+//! ```
+//! use gilrs::record::{Recorder, ReplayTiming};
+//! use gilrs::{Gilrs, GilrsBuilder};
+//!
+//! let mut gilrs = Gilrs::new().unwrap();
+//! let mut recorder = Recorder::new(&gilrs);
+//!
+//! while let Some(ev) = gilrs.next_event() {
+//!     recorder.record(&gilrs, &ev);
+//!     // Handle event…
+//! }
+//!
+//! let recording = recorder.finish();
+//! let mut replay = GilrsBuilder::new()
+//!     .build_replay(recording, ReplayTiming::Immediate)
+//!     .unwrap();
+//!
+//! while let Some(ev) = replay.next_event() {
+//!     // The same events, in the same order.
+//! }
+//! ```
+
+use crate::ev::{Code, Event, EventType};
+use crate::gamepad::{Gamepad, GamepadId, Gilrs};
+use crate::utils;
+use crate::virtual_gamepad::{Injector, VirtualGamepad};
+
+use gilrs_core::AxisInfo;
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Information about recorded gamepad.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RecordedGamepad {
+    /// Name of gamepad supplied by the OS.
+    pub name: String,
+    pub uuid: [u8; 16],
+    /// True if gamepad was connected when recording started.
+    pub is_connected: bool,
+    pub buttons: Vec<Code>,
+    pub axes: Vec<(Code, AxisInfo)>,
+}
+
+impl RecordedGamepad {
+    fn new(gamepad: &Gamepad<'_>, is_connected: bool) -> Self {
+        let inner = gamepad.inner();
+
+        RecordedGamepad {
+            name: gamepad.os_name().to_owned(),
+            uuid: gamepad.uuid(),
+            is_connected,
+            buttons: inner.buttons().iter().map(|&nec| Code(nec)).collect(),
+            axes: inner
+                .axes()
+                .iter()
+                .filter_map(|&nec| inner.axis_info(nec).map(|info| (Code(nec), *info)))
+                .collect(),
+        }
+    }
+}
+
+/// Recorded gamepads and events.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Recording {
+    start: SystemTime,
+    gamepads: Vec<RecordedGamepad>,
+    events: Vec<Event>,
+}
+
+impl Recording {
+    /// Returns time when recording started.
+    pub fn start_time(&self) -> SystemTime {
+        self.start
+    }
+
+    /// Returns recorded gamepads. Index in returned slice is equal to gamepad's ID.
+    pub fn gamepads(&self) -> &[RecordedGamepad] {
+        &self.gamepads
+    }
+
+    /// Returns recorded events.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// Captures events and information about gamepads.
+#[derive(Debug)]
+pub struct Recorder {
+    recording: Recording,
+}
+
+impl Recorder {
+    /// Starts new recording. All gamepads known to `gilrs` are captured immediately.
+    pub fn new(gilrs: &Gilrs) -> Self {
+        let mut recorder = Recorder {
+            recording: Recording {
+                start: utils::time_now(),
+                gamepads: Vec::new(),
+                events: Vec::new(),
+            },
+        };
+
+        let hint = gilrs.last_gamepad_hint();
+        recorder.capture_gamepads(gilrs, hint, None);
+
+        recorder
+    }
+
+    /// Records event. This function should be called with every event returned by
+    /// `gilrs.next_event()`, in the same order.
+    ///
+    /// Information about gamepad is captured when it's seen for the first time, any later
+    /// changes (e.g. different device connected with the same ID) are not recorded.
+    pub fn record(&mut self, gilrs: &Gilrs, event: &Event) {
+        if event.is_dropped() {
+            return;
+        }
+
+        if event.id.0 >= self.recording.gamepads.len() {
+            let connected = if event.event == EventType::Connected {
+                Some(event.id)
+            } else {
+                None
+            };
+            self.capture_gamepads(gilrs, event.id.0 + 1, connected);
+        }
+
+        self.recording.events.push(*event);
+    }
+
+    /// Stops recording and returns recorded data.
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+
+    fn capture_gamepads(&mut self, gilrs: &Gilrs, end: usize, connected: Option<GamepadId>) {
+        let end = end.min(gilrs.last_gamepad_hint());
+
+        for id in self.recording.gamepads.len()..end {
+            let id = GamepadId(id);
+            let gamepad = gilrs.gamepad(id);
+            let is_connected = gamepad.is_connected() && connected != Some(id);
+
+            self.recording
+                .gamepads
+                .push(RecordedGamepad::new(&gamepad, is_connected));
+        }
+    }
+}
+
+/// Decides when replayed events are returned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Events are returned with the same delays between them as when they were recorded.
+    Original,
+    /// All events are available immediately.
+    Immediate,
+}
+
+#[derive(Debug)]
+pub(crate) struct Replay {
+    /// Start of recording, event timestamps are relative to it.
+    start: SystemTime,
+    /// Start of replay. Monotonic, so changes of system clock don't affect pacing.
+    started: Instant,
+    timing: ReplayTiming,
+    events: VecDeque<Event>,
+    injector: Injector,
+}
+
+impl Replay {
+    /// Adds recorded gamepads to virtual backend.
+    pub(crate) fn new(recording: Recording, timing: ReplayTiming, mut injector: Injector) -> Self {
+        for gamepad in recording.gamepads {
+            let mut desc = VirtualGamepad::new(&gamepad.name).uuid(gamepad.uuid);
+
+            for nec in gamepad.buttons {
+                desc = desc.button(nec);
+            }
+
+            for (nec, info) in gamepad.axes {
+                desc = desc.axis(nec, info);
+            }
+
+            let id = injector.add_gamepad(desc);
+
+            if !gamepad.is_connected {
+                injector.disconnect(id);
+            }
+        }
+
+        Replay {
+            start: recording.start,
+            started: Instant::now(),
+            timing,
+            events: recording.events.into(),
+            injector,
+        }
+    }
+
+    /// Returns next event if it's time to return it.
+    pub(crate) fn next_event(&mut self) -> Option<Event> {
+        let due = match (self.timing, self.events.front()) {
+            (_, None) => false,
+            (ReplayTiming::Immediate, Some(_)) => true,
+            (ReplayTiming::Original, Some(ev)) => self.elapsed() >= self.offset(ev),
+        };
+
+        if due {
+            self.events.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Returns time left until next event should be returned or `None` if all events were
+    /// returned.
+    pub(crate) fn time_to_next_event(&self) -> Option<Duration> {
+        let ev = self.events.front()?;
+
+        match self.timing {
+            ReplayTiming::Immediate => Some(Duration::from_secs(0)),
+            ReplayTiming::Original => Some(
+                self.offset(ev)
+                    .checked_sub(self.elapsed())
+                    .unwrap_or_else(|| Duration::from_secs(0)),
+            ),
+        }
+    }
+
+    fn offset(&self, ev: &Event) -> Duration {
+        ev.time
+            .duration_since(self.start)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub(crate) fn injector(&self) -> &Injector {
+        &self.injector
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Button, GilrsBuilder};
+
+    #[test]
+    fn record_and_replay() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let info = AxisInfo {
+            min: -100,
+            max: 100,
+            deadzone: None,
+        };
+        let first = injector.add_gamepad(VirtualGamepad::new("First").button(south));
+        while gilrs.next_event().is_some() {}
+
+        let mut recorder = Recorder::new(&gilrs);
+        let second = injector.add_gamepad(
+            VirtualGamepad::new("Second")
+                .uuid([1; 16])
+                .button(south)
+                .axis(left_x, info),
+        );
+        injector.press(first, south);
+        injector.set_axis(second, left_x, 100);
+        injector.disconnect(first);
+
+        let mut original = Vec::new();
+        while let Some(ev) = gilrs.next_event() {
+            recorder.record(&gilrs, &ev);
+            original.push(ev);
+        }
+
+        let recording = recorder.finish();
+        assert_eq!(recording.gamepads().len(), 2);
+        assert!(recording.gamepads()[0].is_connected);
+        assert!(!recording.gamepads()[1].is_connected);
+        assert_eq!(recording.gamepads()[1].axes.len(), 1);
+
+        let mut replay = GilrsBuilder::new()
+            .build_replay(recording, ReplayTiming::Immediate)
+            .unwrap();
+        assert!(replay.gamepad(first).is_connected());
+        assert!(!replay.gamepad(second).is_connected());
+
+        let replayed = std::iter::from_fn(|| replay.next_event()).collect::<Vec<_>>();
+        assert_eq!(original, replayed);
+        assert!(!replay.is_replaying());
+
+        assert!(!replay.gamepad(first).is_connected());
+        assert!(replay.gamepad(first).is_pressed(Button::South));
+        assert!(replay.gamepad(second).is_connected());
+        assert_eq!(replay.gamepad(second).os_name(), "Second");
+        assert_eq!(replay.gamepad(second).uuid(), [1; 16]);
+        assert_eq!(replay.gamepad(second).value(Axis::LeftStickX), 1.0);
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Waking tasks that wait for events. Used by `Stream` implementation of `Gilrs`.
+
+use gilrs_core::Waiter;
+
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::task::Waker;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often waker thread checks if `Gilrs` still exists.
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Handle to thread that waits for new events and wakes last registered task.
+///
+/// Thread is stopped shortly after this handle is dropped.
+#[derive(Debug)]
+pub(crate) struct EventWaker {
+    tx: Sender<(Waker, Option<Instant>)>,
+}
+
+impl EventWaker {
+    pub(crate) fn new(waiter: Waiter) -> Self {
+        let (tx, rx) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("gilrs-waker".to_owned())
+            .spawn(move || run(waiter, rx))
+            .expect("failed to spawn gilrs-waker thread");
+
+        EventWaker { tx }
+    }
+
+    /// Wakes `waker` when there may be new events or when `deadline` is reached.
+    pub(crate) fn register(&self, waker: Waker, deadline: Option<Instant>) {
+        let _ = self.tx.send((waker, deadline));
+    }
+}
+
+fn run(waiter: Waiter, rx: Receiver<(Waker, Option<Instant>)>) {
+    while let Ok((mut waker, mut deadline)) = rx.recv() {
+        loop {
+            // Only the most recently registered task has to be woken.
+            match rx.try_recv() {
+                Ok(newer) => {
+                    waker = newer.0;
+                    deadline = newer.1;
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => return,
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        waker.wake();
+                        break;
+                    }
+
+                    CHECK_INTERVAL.min(deadline - now)
+                }
+                None => CHECK_INTERVAL,
+            };
+
+            if waiter.wait(Some(timeout)) {
+                waker.wake();
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::{Recorder, ReplayTiming};
+    use crate::virtual_gamepad::VirtualGamepad;
+    use crate::{Button, EventType, GilrsBuilder};
+
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn stream_wakes_on_injected_event() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let id = injector.add_gamepad(VirtualGamepad::new("Pad").button(south));
+
+        let ev = block_on(gilrs.next()).unwrap();
+        assert_eq!(ev.event, EventType::Connected);
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            injector.press(id, south);
+        });
+
+        let ev = block_on(gilrs.next()).unwrap();
+        assert_eq!(ev.event, EventType::ButtonPressed(Button::South, south));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn stream_replays_with_original_timing() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let mut recorder = Recorder::new(&gilrs);
+        let id = injector.add_gamepad(VirtualGamepad::new("Pad").button(south));
+        injector.press(id, south);
+        thread::sleep(Duration::from_millis(50));
+        injector.release(id, south);
+
+        while let Some(ev) = gilrs.next_event() {
+            recorder.record(&gilrs, &ev);
+        }
+
+        let replay = GilrsBuilder::new()
+            .build_replay(recorder.finish(), ReplayTiming::Original)
+            .unwrap();
+        let start = Instant::now();
+        let events = block_on(replay.map(|ev| ev.event).collect::<Vec<_>>());
+
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(
+            events,
+            [
+                EventType::Connected,
+                EventType::ButtonPressed(Button::South, south),
+                EventType::ButtonChanged(Button::South, 1.0, south),
+                EventType::ButtonReleased(Button::South, south),
+                EventType::ButtonChanged(Button::South, 0.0, south),
+            ]
+        );
+    }
+}
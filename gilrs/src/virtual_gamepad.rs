@@ -0,0 +1,160 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Gamepads created from code.
+//!
+//! `Gilrs` created with [`GilrsBuilder::build_virtual()`](../struct.GilrsBuilder.html#method.build_virtual)
+//! doesn't use any real device. Instead, gamepads are added and their input is injected with
+//! [`Injector`](struct.Injector.html). Injected events go through the same pipeline as events from
+//! real gamepads – mappings, filters and cached state work unchanged – so it can be used to test
+//! code that depends on gamepad input on machines without any controllers.
+//!
+//! # Example
+//!
+//! ```
+//! use gilrs::virtual_gamepad::{AxisInfo, VirtualGamepad};
+//! use gilrs::{Axis, Button, EventType, GilrsBuilder};
+//!
+//! let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+//! let south = Button::South.to_nec().unwrap();
+//! let left_x = Axis::LeftStickX.to_nec().unwrap();
+//!
+//! let id = injector.add_gamepad(
+//!     VirtualGamepad::new("Test gamepad")
+//!         .button(south)
+//!         .axis(left_x, AxisInfo { min: -128, max: 127, deadzone: None }),
+//! );
+//! injector.press(id, south);
+//!
+//! while let Some(ev) = gilrs.next_event() {
+//!     println!("{:?}", ev);
+//! }
+//!
+//! assert!(gilrs.gamepad(id).is_pressed(Button::South));
+//! ```
+
+use crate::ev::Code;
+use crate::gamepad::GamepadId;
+
+use gilrs_core::virtual_gamepad::Injector as CoreInjector;
+
+pub use gilrs_core::virtual_gamepad::VirtualGamepad;
+pub use gilrs_core::AxisInfo;
+
+/// Adds virtual gamepads and injects their input.
+///
+/// Input events for disconnected gamepads or elements that gamepad doesn't have are dropped.
+#[derive(Debug)]
+pub struct Injector {
+    inner: CoreInjector,
+}
+
+impl Injector {
+    pub(crate) fn new(inner: CoreInjector) -> Self {
+        Injector { inner }
+    }
+
+    /// Adds new gamepad and returns its ID. `EventType::Connected` will be emitted for it.
+    pub fn add_gamepad(&mut self, gamepad: VirtualGamepad) -> GamepadId {
+        GamepadId(self.inner.add_gamepad(gamepad))
+    }
+
+    /// Presses button.
+    pub fn press(&self, id: GamepadId, code: Code) {
+        self.inner.press(id.0, code);
+    }
+
+    /// Releases button.
+    pub fn release(&self, id: GamepadId, code: Code) {
+        self.inner.release(id.0, code);
+    }
+
+    /// Changes raw value of axis. Value is converted to `f32` using `AxisInfo` of axis, the same
+    /// way as for real devices.
+    pub fn set_axis(&self, id: GamepadId, code: Code, value: i32) {
+        self.inner.set_axis(id.0, code, value);
+    }
+
+    /// Reconnects previously disconnected gamepad.
+    pub fn connect(&self, id: GamepadId) {
+        self.inner.connect(id.0);
+    }
+
+    /// Disconnects gamepad.
+    pub fn disconnect(&self, id: GamepadId) {
+        self.inner.disconnect(id.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Button, EventType, GilrsBuilder};
+
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn mapped_events_and_state() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let id = injector.add_gamepad(VirtualGamepad::new("Pad").button(south).axis(
+            left_x,
+            AxisInfo {
+                min: 0,
+                max: 200,
+                deadzone: Some(0),
+            },
+        ));
+
+        injector.press(id, south);
+        injector.set_axis(id, left_x, 200);
+        injector.disconnect(id);
+
+        let events = std::iter::from_fn(|| gilrs.next_event())
+            .map(|ev| ev.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                EventType::Connected,
+                EventType::ButtonPressed(Button::South, south),
+                EventType::ButtonChanged(Button::South, 1.0, south),
+                EventType::AxisChanged(Axis::LeftStickX, 1.0, left_x),
+                EventType::Disconnected,
+            ]
+        );
+
+        let gamepad = gilrs.gamepad(id);
+        assert!(!gamepad.is_connected());
+        assert!(gamepad.is_pressed(Button::South));
+        assert_eq!(gamepad.value(Axis::LeftStickX), 1.0);
+    }
+
+    #[test]
+    fn blocking_wait() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let id = injector.add_gamepad(VirtualGamepad::new("Pad").button(south));
+        assert_eq!(gilrs.next_event().unwrap().event, EventType::Connected);
+
+        let timeout = Duration::from_millis(20);
+        let start = Instant::now();
+        assert_eq!(gilrs.next_event_blocking(Some(timeout)), None);
+        assert!(start.elapsed() >= timeout);
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            injector.press(id, south);
+        });
+
+        let ev = gilrs.next_event_blocking(None).unwrap();
+        assert_eq!(ev.event, EventType::ButtonPressed(Button::South, south));
+        handle.join().unwrap();
+    }
+}
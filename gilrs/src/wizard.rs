@@ -0,0 +1,693 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Creating mappings interactively.
+//!
+//! [`MappingWizard`](struct.MappingWizard.html) asks for buttons and axes one by one, like SDL's
+//! `controllermap` tool. For every step it waits for the next significant input on the chosen
+//! gamepad and remembers which element of the gamepad was used. When all steps are done, the
+//! result can be applied with [`Gilrs::set_mapping()`](../struct.Gilrs.html#method.set_mapping)
+//! or saved as SDL mapping.
+//!
+//! Input is significant when it moves at least `threshold` (0.5 by default) away from its resting
+//! value, so noise of analog sticks is ignored. Resting value of axis is its value when wizard
+//! was created. If gamepad didn't report it yet, axes that are currently mapped to buttons
+//! (usually triggers) are assumed to rest at minimum and other axes in the middle. Axes that rest
+//! at one end are mapped to buttons as whole axis, axes that rest in the middle as half of axis.
+//! Input is also only accepted when all other elements are at rest and it wasn't already used in
+//! one of previous steps.
+//!
+//! Sticks should be moved to the right (`X` axes) or up (`Y` axes); if axis reports opposite
+//! value, it is mapped as inverted.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use gilrs::wizard::MappingWizard;
+//! use gilrs::Gilrs;
+//!
+//! let mut gilrs = Gilrs::new().unwrap();
+//! let id = match gilrs.gamepads().next() {
+//!     Some((id, _)) => id,
+//!     None => return,
+//! };
+//!
+//! let mut wizard = MappingWizard::new(&gilrs.gamepad(id));
+//! while let Some(step) = wizard.step() {
+//!     println!("Press {:?} (or skip it)", step);
+//!
+//!     // Wait for input and handle it. Call `wizard.skip()` if gamepad doesn't have the element
+//!     // or `wizard.undo()` to go back to previous step.
+//!     while let Some(ev) = gilrs.next_event_blocking(None) {
+//!         if wizard.handle_event(&ev) {
+//!             break;
+//!         }
+//!     }
+//! }
+//!
+//! println!("{}", wizard.sdl_mapping(None).unwrap());
+//! gilrs.set_mapping(id.into(), &wizard.mapping(), None).unwrap();
+//! ```
+
+use fnv::FnvHashMap;
+use gilrs_core::native_ev_codes as nec;
+use gilrs_core::EvCode;
+use uuid::Uuid;
+
+use crate::ev::{Axis, AxisOrBtn, Button, Code, Event, EventType};
+use crate::gamepad::{Gamepad, GamepadId};
+use crate::mapping::{AxisRange, HatDirection, Mapping, MappingData, MappingError, MappingInput};
+
+/// Steps used by `MappingWizard::new()`, in the same order as in SDL's `controllermap`.
+static STEPS: [AxisOrBtn; 27] = [
+    AxisOrBtn::Btn(Button::South),
+    AxisOrBtn::Btn(Button::East),
+    AxisOrBtn::Btn(Button::West),
+    AxisOrBtn::Btn(Button::North),
+    AxisOrBtn::Btn(Button::Select),
+    AxisOrBtn::Btn(Button::Mode),
+    AxisOrBtn::Btn(Button::Start),
+    AxisOrBtn::Btn(Button::LeftThumb),
+    AxisOrBtn::Btn(Button::RightThumb),
+    AxisOrBtn::Btn(Button::LeftTrigger),
+    AxisOrBtn::Btn(Button::RightTrigger),
+    AxisOrBtn::Btn(Button::DPadUp),
+    AxisOrBtn::Btn(Button::DPadDown),
+    AxisOrBtn::Btn(Button::DPadLeft),
+    AxisOrBtn::Btn(Button::DPadRight),
+    AxisOrBtn::Btn(Button::Misc1),
+    AxisOrBtn::Btn(Button::Paddle1),
+    AxisOrBtn::Btn(Button::Paddle2),
+    AxisOrBtn::Btn(Button::Paddle3),
+    AxisOrBtn::Btn(Button::Paddle4),
+    AxisOrBtn::Btn(Button::Touchpad),
+    AxisOrBtn::Axis(Axis::LeftStickX),
+    AxisOrBtn::Axis(Axis::LeftStickY),
+    AxisOrBtn::Axis(Axis::RightStickX),
+    AxisOrBtn::Axis(Axis::RightStickY),
+    AxisOrBtn::Btn(Button::LeftTrigger2),
+    AxisOrBtn::Btn(Button::RightTrigger2),
+];
+
+/// State machine that creates mapping for one gamepad from its input.
+///
+/// See [module documentation](index.html) for details.
+#[derive(Clone, Debug)]
+pub struct MappingWizard {
+    id: GamepadId,
+    uuid: Uuid,
+    name: String,
+    buttons: Vec<EvCode>,
+    axes: Vec<EvCode>,
+    steps: Vec<AxisOrBtn>,
+    /// Input for each finished step, `None` if step was skipped.
+    inputs: Vec<Option<MappingInput>>,
+    resting: FnvHashMap<Code, f32>,
+    /// Elements that are currently away from their resting value.
+    active: Vec<Code>,
+    threshold: f32,
+}
+
+impl MappingWizard {
+    /// Creates wizard that asks for all buttons and axes supported by SDL mappings.
+    pub fn new(gamepad: &Gamepad<'_>) -> Self {
+        Self::with_steps(gamepad, &STEPS)
+    }
+
+    /// Creates wizard that asks for `steps`, in that order. `Button::Unknown`, `Axis::Unknown`
+    /// and dpad axes can't be mapped and are removed from `steps`.
+    pub fn with_steps(gamepad: &Gamepad<'_>, steps: &[AxisOrBtn]) -> Self {
+        let inner = gamepad.inner();
+        let axes = inner.axes().to_vec();
+
+        let state = gamepad.state();
+        let mut resting = FnvHashMap::default();
+        for &axis in &axes {
+            let code = Code(axis);
+            let mapped = gamepad.axis_or_btn_name(code);
+            let value = match (state.axis_data(code), state.button_data(code), mapped) {
+                (Some(data), _, Some(AxisOrBtn::Axis(axis))) => unflip(axis, data.value()),
+                (Some(data), _, _) => data.value(),
+                (None, Some(data), _) => data.value() * 2.0 - 1.0,
+                (None, None, Some(AxisOrBtn::Btn(_))) => -1.0,
+                (None, None, _) => 0.0,
+            };
+            resting.insert(code, value);
+        }
+
+        MappingWizard {
+            id: gamepad.id(),
+            uuid: Uuid::from_bytes(gamepad.uuid()),
+            name: gamepad.os_name().to_owned(),
+            buttons: inner.buttons().to_vec(),
+            axes,
+            steps: steps
+                .iter()
+                .cloned()
+                .filter(|&step| match step {
+                    AxisOrBtn::Btn(btn) => btn != Button::Unknown,
+                    AxisOrBtn::Axis(axis) => {
+                        axis != Axis::Unknown && axis != Axis::DPadX && axis != Axis::DPadY
+                    }
+                })
+                .collect(),
+            inputs: Vec::new(),
+            resting,
+            active: Vec::new(),
+            threshold: 0.5,
+        }
+    }
+
+    /// Sets how far from resting value, in range [0.0, 2.0], axis has to move to be accepted.
+    /// Default is 0.5.
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    /// Returns ID of gamepad that is being mapped.
+    pub fn gamepad_id(&self) -> GamepadId {
+        self.id
+    }
+
+    /// Returns all steps.
+    pub fn steps(&self) -> &[AxisOrBtn] {
+        &self.steps
+    }
+
+    /// Returns index of current step in [`steps()`](#method.steps).
+    pub fn step_index(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns button or axis that wizard currently waits for, or `None` if all steps are done.
+    pub fn step(&self) -> Option<AxisOrBtn> {
+        self.steps.get(self.inputs.len()).cloned()
+    }
+
+    /// Returns true if all steps are done.
+    pub fn is_finished(&self) -> bool {
+        self.inputs.len() >= self.steps.len()
+    }
+
+    /// Handles event and returns true if it finished current step. Events of other gamepads and
+    /// input that can't be used for current step are ignored.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        if event.id != self.id {
+            return false;
+        }
+
+        let (code, value) = match self.raw_value(event.event) {
+            Some(raw) => raw,
+            None => return false,
+        };
+
+        let rest = self.resting.get(&code).cloned().unwrap_or(0.0);
+
+        let was_active = self.active.contains(&code);
+        if (value - rest).abs() < self.threshold {
+            self.active.retain(|&c| c != code);
+            return false;
+        } else if was_active {
+            return false;
+        }
+
+        self.active.push(code);
+        if self.active.len() > 1 {
+            // Some other element is still pressed.
+            return false;
+        }
+
+        let step = match self.step() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        let input = match self.input_for(step, code, value, rest) {
+            Some(input) => input,
+            None => return false,
+        };
+
+        if self
+            .inputs
+            .iter()
+            .flatten()
+            .any(|&used| conflicts(used, input))
+        {
+            return false;
+        }
+
+        self.inputs.push(Some(input));
+
+        true
+    }
+
+    /// Skips current step. Does nothing if all steps are done.
+    pub fn skip(&mut self) {
+        if !self.is_finished() {
+            self.inputs.push(None);
+        }
+    }
+
+    /// Goes back to previous step and forgets its input. Returns false if wizard is at first step.
+    pub fn undo(&mut self) -> bool {
+        self.inputs.pop().is_some()
+    }
+
+    /// Returns element of gamepad mapped to `el` in already finished steps.
+    pub fn input(&self, el: AxisOrBtn) -> Option<MappingInput> {
+        self.steps
+            .iter()
+            .zip(&self.inputs)
+            .rev()
+            .filter(|&(&step, _)| step == el)
+            .find_map(|(_, &input)| input)
+    }
+
+    /// Returns mapping created from finished steps.
+    pub fn mapping(&self) -> MappingData {
+        let mut data = MappingData::new();
+
+        for (&step, &input) in self.steps.iter().zip(&self.inputs) {
+            if let Some(input) = input {
+                match step {
+                    AxisOrBtn::Btn(btn) => data.insert_btn_input(input, btn),
+                    AxisOrBtn::Axis(axis) => data.insert_axis_input(input, axis, AxisRange::Full),
+                };
+            }
+        }
+
+        data
+    }
+
+    /// Returns mapping created from finished steps in SDL format. If `name` is `None`, gamepad
+    /// name reported by driver will be used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MappingError::InvalidName` if `name` contains comma.
+    pub fn sdl_mapping<'b, O: Into<Option<&'b str>>>(
+        &self,
+        name: O,
+    ) -> Result<String, MappingError> {
+        let name = name.into().unwrap_or(&self.name);
+
+        Mapping::from_data(&self.mapping(), &self.buttons, &self.axes, name, self.uuid)
+            .map(|(_, sdl)| sdl)
+    }
+
+    /// Returns code and value of element that changed. Values of buttons are in range
+    /// [0.0, 1.0], values of axes in range [-1.0, 1.0], not affected by current mapping.
+    fn raw_value(&self, event: EventType) -> Option<(Code, f32)> {
+        let (code, value) = match event {
+            EventType::ButtonPressed(_, code) => (code, 1.0),
+            EventType::ButtonReleased(_, code) => (code, 0.0),
+            EventType::ButtonChanged(_, value, code) => (code, value),
+            EventType::AxisChanged(axis, value, code) if self.axes.contains(&code.0) => {
+                return Some((code, unflip(axis, value)));
+            }
+            EventType::AxisChanged(_, value, code) => (code, value),
+            _ => return None,
+        };
+
+        if self.axes.contains(&code.0) {
+            // Axis mapped to button.
+            Some((code, value * 2.0 - 1.0))
+        } else if self.buttons.contains(&code.0) || filtered_dpad(code).is_some() {
+            Some((code, value))
+        } else {
+            None
+        }
+    }
+
+    fn input_for(
+        &self,
+        step: AxisOrBtn,
+        code: Code,
+        value: f32,
+        rest: f32,
+    ) -> Option<MappingInput> {
+        let is_axis = self.axes.contains(&code.0);
+
+        match step {
+            AxisOrBtn::Btn(btn) => {
+                if btn.is_dpad() && !self.buttons.contains(&code.0) {
+                    if let Some(direction) = hat_direction(code, value, rest) {
+                        return Some(MappingInput::Hat { hat: 0, direction });
+                    }
+                }
+
+                if !is_axis {
+                    return if self.buttons.contains(&code.0) {
+                        Some(MappingInput::Button(code))
+                    } else {
+                        None
+                    };
+                }
+
+                let positive = value > rest;
+                let (range, inverted) = if rest < -0.5 && positive {
+                    (AxisRange::Full, false)
+                } else if rest > 0.5 && !positive {
+                    (AxisRange::Full, true)
+                } else if positive {
+                    (AxisRange::UpperHalf, false)
+                } else {
+                    (AxisRange::LowerHalf, false)
+                };
+
+                Some(MappingInput::Axis {
+                    code,
+                    range,
+                    inverted,
+                })
+            }
+            AxisOrBtn::Axis(axis) if is_axis => Some(MappingInput::Axis {
+                code,
+                range: AxisRange::Full,
+                // Value of axis moved up is negative on platforms with reversed Y axis.
+                inverted: (value > rest) == (gilrs_core::IS_Y_AXIS_REVERSED && is_y_axis(axis)),
+            }),
+            AxisOrBtn::Axis(_) => None,
+        }
+    }
+}
+
+/// Reverts change of Y axes direction done by `Gilrs` on some platforms.
+fn unflip(axis: Axis, value: f32) -> f32 {
+    if gilrs_core::IS_Y_AXIS_REVERSED && is_y_axis(axis) {
+        -value
+    } else {
+        value
+    }
+}
+
+fn is_y_axis(axis: Axis) -> bool {
+    axis == Axis::LeftStickY || axis == Axis::RightStickY || axis == Axis::DPadY
+}
+
+/// Returns direction of hat switch that was pressed, if `code` is dpad axis or dpad button
+/// created from it by `axis_dpad_to_button` filter.
+fn hat_direction(code: Code, value: f32, rest: f32) -> Option<HatDirection> {
+    let positive = value > rest;
+
+    if code.0 == nec::AXIS_DPADX {
+        Some(if positive {
+            HatDirection::Right
+        } else {
+            HatDirection::Left
+        })
+    } else if code.0 == nec::AXIS_DPADY {
+        // Same as `unflip()`, up is positive after Gilrs reverses the axis.
+        Some(if positive != gilrs_core::IS_Y_AXIS_REVERSED {
+            HatDirection::Up
+        } else {
+            HatDirection::Down
+        })
+    } else {
+        filtered_dpad(code)
+    }
+}
+
+fn filtered_dpad(code: Code) -> Option<HatDirection> {
+    match code.0 {
+        c if c == nec::BTN_DPAD_UP => Some(HatDirection::Up),
+        c if c == nec::BTN_DPAD_DOWN => Some(HatDirection::Down),
+        c if c == nec::BTN_DPAD_LEFT => Some(HatDirection::Left),
+        c if c == nec::BTN_DPAD_RIGHT => Some(HatDirection::Right),
+        _ => None,
+    }
+}
+
+/// Returns true if both inputs use the same part of the same element.
+fn conflicts(a: MappingInput, b: MappingInput) -> bool {
+    match (a, b) {
+        (
+            MappingInput::Axis {
+                code: a, range: ra, ..
+            },
+            MappingInput::Axis {
+                code: b, range: rb, ..
+            },
+        ) => a == b && (ra == rb || ra == AxisRange::Full || rb == AxisRange::Full),
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_gamepad::{AxisInfo, VirtualGamepad};
+    use crate::{Gilrs, GilrsBuilder};
+
+    fn handle_events(gilrs: &mut Gilrs, wizard: &mut MappingWizard) -> bool {
+        let mut finished_step = false;
+        while let Some(ev) = gilrs.next_event() {
+            finished_step |= wizard.handle_event(&ev);
+        }
+        finished_step
+    }
+
+    #[test]
+    fn wizard() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let south = Button::South.to_nec().unwrap();
+        let east = Button::East.to_nec().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let left_y = Axis::LeftStickY.to_nec().unwrap();
+        let lt2 = Code(nec::AXIS_LT2);
+        let dpad_x = Code(nec::AXIS_DPADX);
+        let stick = AxisInfo {
+            min: -128,
+            max: 127,
+            deadzone: None,
+        };
+
+        let id = injector.add_gamepad(
+            VirtualGamepad::new("Pad")
+                .button(south)
+                .button(east)
+                .axis(left_x, stick)
+                .axis(left_y, stick)
+                .axis(
+                    lt2,
+                    AxisInfo {
+                        min: 0,
+                        max: 255,
+                        deadzone: None,
+                    },
+                )
+                .axis(
+                    dpad_x,
+                    AxisInfo {
+                        min: -1,
+                        max: 1,
+                        deadzone: None,
+                    },
+                ),
+        );
+        while gilrs.next_event().is_some() {}
+
+        let steps = [
+            AxisOrBtn::Btn(Button::South),
+            AxisOrBtn::Btn(Button::East),
+            AxisOrBtn::Axis(Axis::LeftStickX),
+            AxisOrBtn::Axis(Axis::LeftStickY),
+            AxisOrBtn::Btn(Button::LeftTrigger2),
+            AxisOrBtn::Btn(Button::DPadRight),
+            AxisOrBtn::Axis(Axis::Unknown),
+        ];
+        let mut wizard = MappingWizard::with_steps(&gilrs.gamepad(id), &steps);
+        assert_eq!(wizard.steps(), &steps[..6]);
+
+        // Wrong button, undo it.
+        injector.press(id, east);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        assert_eq!(wizard.step(), Some(AxisOrBtn::Btn(Button::East)));
+        assert!(wizard.undo());
+        assert!(!wizard.undo());
+
+        // East is still pressed.
+        injector.press(id, south);
+        assert!(!handle_events(&mut gilrs, &mut wizard));
+        injector.release(id, east);
+        injector.release(id, south);
+        assert!(!handle_events(&mut gilrs, &mut wizard));
+        injector.press(id, south);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        injector.release(id, south);
+        handle_events(&mut gilrs, &mut wizard);
+
+        wizard.skip();
+        assert_eq!(wizard.step_index(), 2);
+
+        // Noise, button that is already used, then stick moved to the right.
+        injector.set_axis(id, left_x, 20);
+        injector.press(id, south);
+        injector.release(id, south);
+        assert!(!handle_events(&mut gilrs, &mut wizard));
+        injector.set_axis(id, left_x, 127);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        injector.set_axis(id, left_x, 0);
+        handle_events(&mut gilrs, &mut wizard);
+
+        // Stick moved down.
+        injector.set_axis(id, left_y, 127);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        injector.set_axis(id, left_y, 0);
+        handle_events(&mut gilrs, &mut wizard);
+
+        injector.set_axis(id, lt2, 255);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        injector.set_axis(id, lt2, 0);
+        handle_events(&mut gilrs, &mut wizard);
+
+        injector.set_axis(id, dpad_x, 1);
+        assert!(handle_events(&mut gilrs, &mut wizard));
+        assert!(wizard.is_finished());
+        assert_eq!(wizard.step(), None);
+
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::South)),
+            Some(MappingInput::Button(south))
+        );
+        assert_eq!(wizard.input(AxisOrBtn::Btn(Button::East)), None);
+        assert_eq!(
+            wizard.input(AxisOrBtn::Axis(Axis::LeftStickX)),
+            Some(MappingInput::axis(left_x))
+        );
+        assert_eq!(
+            wizard.input(AxisOrBtn::Axis(Axis::LeftStickY)),
+            Some(MappingInput::Axis {
+                code: left_y,
+                range: AxisRange::Full,
+                inverted: gilrs_core::IS_Y_AXIS_REVERSED,
+            })
+        );
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::LeftTrigger2)),
+            Some(MappingInput::axis(lt2))
+        );
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::DPadRight)),
+            Some(MappingInput::Hat {
+                hat: 0,
+                direction: HatDirection::Right,
+            })
+        );
+
+        let sdl = wizard.sdl_mapping("Wizard pad").unwrap();
+        assert!(sdl.starts_with("00000000000000000000000000000000,Wizard pad,a:b0,"));
+        assert!(sdl.contains(",dpright:h0.2,"));
+        assert!(sdl.contains(",lefttrigger:a2,"));
+
+        gilrs
+            .set_mapping(id.into(), &wizard.mapping(), "Wizard pad")
+            .unwrap();
+        assert_eq!(gilrs.gamepad(id).name(), "Wizard pad");
+    }
+
+    #[test]
+    fn wizard_mapping_events() {
+        let (mut gilrs, mut injector) = GilrsBuilder::new().build_virtual().unwrap();
+        let left_x = Axis::LeftStickX.to_nec().unwrap();
+        let left_y = Axis::LeftStickY.to_nec().unwrap();
+        let rt2 = Code(nec::AXIS_RT2);
+        let stick = AxisInfo {
+            min: -127,
+            max: 127,
+            deadzone: None,
+        };
+        let trigger = AxisInfo {
+            min: 0,
+            max: 100,
+            deadzone: None,
+        };
+
+        let id = injector.add_gamepad(
+            VirtualGamepad::new("Pad")
+                .axis(left_x, stick)
+                .axis(left_y, stick)
+                .axis(rt2, trigger),
+        );
+        // Trigger that rests at maximum.
+        injector.set_axis(id, rt2, 100);
+        while gilrs.next_event().is_some() {}
+
+        let steps = [
+            AxisOrBtn::Btn(Button::LeftTrigger2),
+            AxisOrBtn::Btn(Button::RightTrigger2),
+            AxisOrBtn::Axis(Axis::LeftStickY),
+            AxisOrBtn::Btn(Button::LeftTrigger),
+        ];
+        let mut wizard = MappingWizard::with_steps(&gilrs.gamepad(id), &steps);
+        let mut step = |code, value, rest| {
+            injector.set_axis(id, code, value);
+            assert!(handle_events(&mut gilrs, &mut wizard));
+            injector.set_axis(id, code, rest);
+            handle_events(&mut gilrs, &mut wizard);
+        };
+        step(left_x, -127, 0);
+        step(left_x, 127, 0);
+        // Stick moved up.
+        let up = if gilrs_core::IS_Y_AXIS_REVERSED {
+            -127
+        } else {
+            127
+        };
+        step(left_y, up, 0);
+        step(rt2, 0, 100);
+        assert!(wizard.is_finished());
+
+        let axis = |code, range, inverted| {
+            Some(MappingInput::Axis {
+                code,
+                range,
+                inverted,
+            })
+        };
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::LeftTrigger2)),
+            axis(left_x, AxisRange::LowerHalf, false)
+        );
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::RightTrigger2)),
+            axis(left_x, AxisRange::UpperHalf, false)
+        );
+        assert_eq!(
+            wizard.input(AxisOrBtn::Btn(Button::LeftTrigger)),
+            axis(rt2, AxisRange::Full, true)
+        );
+
+        gilrs
+            .set_mapping(id.into(), &wizard.mapping(), None)
+            .unwrap();
+        while gilrs.next_event().is_some() {}
+
+        injector.set_axis(id, left_x, -127);
+        injector.set_axis(id, left_x, 127);
+        injector.set_axis(id, left_y, up);
+        injector.set_axis(id, rt2, 0);
+        let events = std::iter::from_fn(|| gilrs.next_event())
+            .map(|ev| ev.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                EventType::ButtonPressed(Button::LeftTrigger2, left_x),
+                EventType::ButtonChanged(Button::LeftTrigger2, 1.0, left_x),
+                EventType::ButtonReleased(Button::LeftTrigger2, left_x),
+                EventType::ButtonChanged(Button::LeftTrigger2, 0.0, left_x),
+                EventType::ButtonPressed(Button::RightTrigger2, left_x),
+                EventType::ButtonChanged(Button::RightTrigger2, 1.0, left_x),
+                EventType::AxisChanged(Axis::LeftStickY, 1.0, left_y),
+                EventType::ButtonPressed(Button::LeftTrigger, rt2),
+                EventType::ButtonChanged(Button::LeftTrigger, 1.0, rt2),
+            ]
+        );
+        assert!(gilrs.gamepad(id).is_pressed(Button::LeftTrigger));
+    }
+}